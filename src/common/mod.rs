@@ -1,2 +1,2 @@
 pub(crate) mod peekable_ext;
-pub(crate) mod position;
+pub mod position;
@@ -1,4 +1,4 @@
-use std::iter::Peekable;
+use std::{collections::VecDeque, iter::Peekable};
 
 pub(crate) trait ConsumeIf<T, I>
 where
@@ -20,20 +20,75 @@ where
     {
         self.consume_if(predicate).is_some()
     }
+}
 
-    fn consume_while<F>(&mut self, mut predicate: F) -> Option<I>
+impl<T, I> ConsumeIf<T, I> for Peekable<T>
+where
+    T: Iterator<Item = I>,
+{
+    fn consume_if<F>(&mut self, mut predicate: F) -> Option<I>
     where
         F: FnMut(&I) -> bool,
     {
-        let mut last = None;
-        while let Some(item) = self.consume_if(&mut predicate) {
-            last = Some(item);
+        if let Some(item) = self.peek() {
+            if predicate(item) {
+                return self.next();
+            }
         }
-        last
+        None
     }
 }
 
-impl<T, I> ConsumeIf<T, I> for Peekable<T>
+/// Like [`Peekable`], but can look any number of elements ahead
+pub(crate) struct MultiPeekable<T>
+where
+    T: Iterator,
+{
+    iter: T,
+    buffer: VecDeque<T::Item>,
+}
+
+impl<T> MultiPeekable<T>
+where
+    T: Iterator,
+{
+    pub(crate) fn new(iter: T) -> Self {
+        Self {
+            iter,
+            buffer: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn peek(&mut self) -> Option<&T::Item> {
+        self.peek_nth(0)
+    }
+
+    /// Puts an element back, it is returned by the next call to `next()`
+    pub(crate) fn push_front(&mut self, item: T::Item) {
+        self.buffer.push_front(item);
+    }
+
+    /// Returns the element `n` places ahead without consuming anything, `peek_nth(0)` is `peek()`
+    pub(crate) fn peek_nth(&mut self, n: usize) -> Option<&T::Item> {
+        while self.buffer.len() <= n {
+            self.buffer.push_back(self.iter.next()?);
+        }
+        self.buffer.get(n)
+    }
+}
+
+impl<T> Iterator for MultiPeekable<T>
+where
+    T: Iterator,
+{
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.pop_front().or_else(|| self.iter.next())
+    }
+}
+
+impl<T, I> ConsumeIf<T, I> for MultiPeekable<T>
 where
     T: Iterator<Item = I>,
 {
@@ -4,17 +4,18 @@ use std::{
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub(crate) struct Position {
+pub struct Position {
     // The byte index in the current working statement.
     // only for internal use, not for display
-    pub(crate) index: usize,
-    pub(crate) absolute: AbsolutePosition,
+    pub index: usize,
+    pub absolute: AbsolutePosition,
 }
 
+/// Where a character is in the source, the column counts characters from the start of the row
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub(crate) struct AbsolutePosition {
-    pub(crate) row: usize,
-    pub(crate) col: usize,
+pub struct AbsolutePosition {
+    pub row: usize,
+    pub col: usize,
 }
 
 impl Add<usize> for Position {
@@ -45,15 +46,15 @@ impl Add for Position {
     }
 }
 
+/// Moves past a character, `index` counts its bytes and the column counts it once
 impl AddAssign<char> for Position {
     fn add_assign(&mut self, rhs: char) {
-        let len = rhs.len_utf8();
-        self.index += len;
+        self.index += rhs.len_utf8();
         if rhs == '\n' {
             self.absolute.row += 1;
             self.absolute.col = 0;
         } else {
-            self.absolute.col += len;
+            self.absolute.col += 1;
         }
     }
 }
@@ -68,12 +69,35 @@ impl AddAssign<&str> for Position {
 
 impl Display for Position {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}:{}", self.absolute.row, self.absolute.col)
+        // rows and columns count from zero internally
+        write!(f, "{}:{}", self.absolute.row + 1, self.absolute.col + 1)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) struct Span {
-    pub(crate) start: Position,
-    pub(crate) end: Position,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Position;
+
+    #[test]
+    fn columns_count_characters_and_index_counts_bytes() {
+        let mut position = Position::default();
+        position += "é€x";
+        assert_eq!(position.index, 6);
+        assert_eq!(position.absolute.col, 3);
+        assert_eq!(position.to_string(), "1:4");
+    }
+
+    #[test]
+    fn newlines_start_a_row() {
+        let mut position = Position::default();
+        position += "ab\nc";
+        assert_eq!(position.index, 4);
+        assert_eq!(position.to_string(), "2:2");
+    }
 }
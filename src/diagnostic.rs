@@ -0,0 +1,252 @@
+use std::fmt::Display;
+
+use crate::{error::DBError, source::RawStatement};
+
+/// Renders an error the way rustc does, with the offending line and the span underlined:
+///
+/// ```text
+/// error: Unexpected token: found c, expected one of `,` `FROM` `;`
+///  --> query.sql:1:12
+///   |
+/// 1 | SELECT a b c FROM t;
+///   |            ^
+/// ```
+pub(crate) struct Diagnostic<'a> {
+    error: &'a DBError,
+    statement: &'a RawStatement,
+    file_name: Option<&'a str>,
+    color: bool,
+}
+
+impl<'a> Diagnostic<'a> {
+    pub(crate) fn new(
+        error: &'a DBError,
+        statement: &'a RawStatement,
+        file_name: Option<&'a str>,
+    ) -> Self {
+        Self {
+            error,
+            statement,
+            file_name,
+            color: false,
+        }
+    }
+
+    /// Colors the output with ANSI escapes, meant for terminals
+    pub(crate) fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    fn paint(&self, text: &str, style: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", style, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// The text of a source row that belongs to the statement, and the column it starts at
+    fn line(&self, row: usize) -> (&'a str, usize) {
+        let start = self.statement.span.start.absolute;
+        let line = self
+            .statement
+            .content
+            .split('\n')
+            .nth(row.saturating_sub(start.row))
+            .unwrap_or_default()
+            .trim_end_matches('\r');
+        let column = if row == start.row { start.col } else { 0 };
+        (line, column)
+    }
+}
+
+/// Tabs are echoed as four spaces like rustc does, so the underline lines up in any terminal
+const TAB: &str = "    ";
+
+/// The number of columns `text` takes once its tabs are expanded
+fn width(text: &[char]) -> usize {
+    text.iter()
+        .map(|&c| if c == '\t' { TAB.len() } else { 1 })
+        .sum()
+}
+
+const ERROR: &str = "1;31";
+const GUTTER: &str = "1;34";
+const BOLD: &str = "1";
+
+impl Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let span = self.error.span();
+        let start = span.start.absolute;
+        let end = span.end.absolute;
+
+        writeln!(
+            f,
+            "{}: {}",
+            self.paint("error", ERROR),
+            self.paint(&self.error.describe(Some(self.statement)), BOLD)
+        )?;
+
+        let line_number = (start.row + 1).to_string();
+        let gutter = " ".repeat(line_number.len());
+        let bar = self.paint("|", GUTTER);
+
+        let location = match self.file_name {
+            Some(file_name) => format!("{}:{}", file_name, span.start),
+            None => span.start.to_string(),
+        };
+        writeln!(f, "{}{} {}", gutter, self.paint("-->", GUTTER), location)?;
+        writeln!(f, "{} {}", gutter, bar)?;
+
+        // the start of a line may belong to the previous statement, it is left blank
+        let (line, line_column) = self.line(start.row);
+        writeln!(
+            f,
+            "{} {} {}{}",
+            self.paint(&line_number, GUTTER),
+            bar,
+            " ".repeat(line_column),
+            line.replace('\t', TAB)
+        )?;
+
+        // columns count characters, the underline counts them with their tabs expanded
+        let chars: Vec<char> = line.chars().collect();
+        let offset = start.col.saturating_sub(line_column).min(chars.len());
+        let remaining = chars.len() - offset;
+        let length = if end.row == start.row {
+            end.col.saturating_sub(start.col) + 1
+        } else {
+            remaining
+        };
+        // the end of the input lies right after the line, where nothing is left to underline
+        let underline = width(&chars[offset..offset + length.min(remaining)]).max(1);
+        writeln!(
+            f,
+            "{} {} {}{}",
+            gutter,
+            bar,
+            " ".repeat(line_column + width(&chars[..offset])),
+            self.paint(&"^".repeat(underline), ERROR)
+        )?;
+
+        if let Some(help) = self.error.help() {
+            writeln!(f, "{} {}", gutter, bar)?;
+            writeln!(
+                f,
+                "{} {} {}: {}",
+                gutter,
+                self.paint("=", GUTTER),
+                self.paint("help", BOLD),
+                help
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, parse_statements};
+
+    fn render(sql: &str) -> String {
+        parse(sql).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn points_at_the_token() {
+        assert_eq!(
+            render("SELECT a b c FROM t;"),
+            "\
+error: Unexpected token: found c, expected one of `,` `FROM` `WHERE` `GROUP` `HAVING` `WINDOW` `INTERSECT` `UNION` `EXCEPT` `ORDER` `LIMIT` `OFFSET` `;`
+ --> 1:12
+  |
+1 | SELECT a b c FROM t;
+  |            ^
+"
+        );
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let rendered = render("SELECT 'é' 'x';");
+        assert!(rendered.contains(" --> 1:12\n"), "{}", rendered);
+        assert!(
+            rendered.ends_with("1 | SELECT 'é' 'x';\n  |            ^^^\n"),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn expands_tabs_before_the_underline() {
+        let rendered = render("SELECT\ta\tb c;");
+        assert!(rendered.contains(" --> 1:12\n"), "{}", rendered);
+        assert!(
+            rendered.contains("1 | SELECT    a    b c;\n  |                  ^\n"),
+            "{}",
+            rendered
+        );
+
+        let rendered = render("SELECT a FROM\t'a\tb' t;");
+        assert!(
+            rendered.contains("1 | SELECT a FROM    'a    b' t;\n  |                  ^^^^^^^^\n"),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn underlines_after_wide_characters_on_the_line() {
+        let rendered = render("SELECT 'ü'; SELECT FROM;\nSELECT 1;");
+        assert!(
+            rendered.contains(
+                " --> 1:20\n  |\n1 | SELECT 'ü'; SELECT FROM;\n  |                    ^^^^\n"
+            ),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn blanks_other_statements_on_the_line() {
+        let rendered: Vec<String> = parse_statements("SELECT 'ü'; SELECT FROM; SELECT 1;")
+            .filter_map(|result| result.err())
+            .map(|errors| errors.to_string())
+            .collect();
+        assert_eq!(rendered.len(), 1);
+        assert!(
+            rendered[0].contains("1 |             SELECT FROM;\n  |                    ^^^^\n"),
+            "{}",
+            rendered[0]
+        );
+    }
+
+    #[test]
+    fn shows_help_notes() {
+        let rendered = render("SELECT 'abc");
+        assert!(
+            rendered.starts_with("error: Unterminated string\n"),
+            "{}",
+            rendered
+        );
+        assert!(
+            rendered.ends_with(
+                "  = help: close the string with `'`, a quote inside is written as `''`\n"
+            ),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn points_past_the_end_of_the_input() {
+        let rendered = render("SELECT a FROM");
+        assert!(
+            rendered.contains("1 | SELECT a FROM\n  |              ^\n"),
+            "{}",
+            rendered
+        );
+    }
+}
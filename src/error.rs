@@ -1,74 +1,378 @@
+use std::{
+    fmt::Display,
+    io::{self, IsTerminal},
+};
+
 use crate::{
-    common::position::{Position, Span},
-    lexer::{keyword::Keyword, token::TokenKind, Token},
+    common::position::Span,
+    diagnostic::Diagnostic,
+    lexer::{symbol::Symbol, token::TokenKind, Token},
+    parser::statements::query::SetOperator,
     source::RawStatement,
 };
 
 #[derive(Debug, Clone)]
-pub(crate) enum DBError {
+pub enum DBError {
     // Lexer errors
     UnTerminatedString(Span),
+    UnTerminatedComment(Span),
+    UnTerminatedIdentifier(Span),
+    EmptyIdentifier(Span),
     UnterminatedFloat(Span),
-    IllegalCharacter(char, Position),
+    IntegerOutOfRange(Span),
+    FloatOutOfRange(Span),
+    /// A number with an exponent missing its digits, or running into a name
+    MalformedNumber(Span),
+    IllegalCharacter(char, Span),
 
     // Parser errors
+    /// `expected` holds everything that was valid at this point
     Unexpected {
         found: Token,
-        expected: TokenKind,
+        expected: Vec<Expected>,
     },
+    /// `span` is the empty span right after the last token
     UnexpectedEof {
-        expected: TokenKind,
+        expected: Vec<Expected>,
+        span: Span,
     },
     UnexpectedToken {
         found: Token,
     },
-    UnexpectedKeyword {
-        found: Keyword,
-        allowed: Vec<Keyword>,
-    },
     IdentExpected(Token),
-    KeywordExpected(Token),
     DatatypeExpected(Token),
-    UnexpectedStatement,
-    Eof,
+    InvalidTypeParameter(Token),
+    DateTimeFieldExpected(Token),
+    ScaleExceedsPrecision {
+        scale: u32,
+        precision: u32,
+        span: Span,
+    },
+    ColumnCountMismatch {
+        operator: SetOperator,
+        left: usize,
+        right: usize,
+        span: Span,
+    },
+    /// A window frame that can't hold any row, `span` covers the offending bounds
+    InvalidFrame {
+        reason: FrameError,
+        span: Span,
+    },
+    /// A token that doesn't start any statement
+    UnexpectedStatement(Token),
+    /// The input ended, the parser turns it into `UnexpectedEof` where it matters
+    Eof(Span),
+}
+
+/// What makes a window frame empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    StartsUnboundedFollowing,
+    EndsUnboundedPreceding,
+    /// The start bound comes after the end bound, or after the current row without one
+    StartsAfterEnd,
+}
+
+/// Something the parser would have accepted where an error occurred
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+    Token(TokenKind),
+    /// Any of the tokens that can start an expression
+    Expression,
+}
+
+impl From<TokenKind> for Expected {
+    fn from(kind: TokenKind) -> Self {
+        Expected::Token(kind)
+    }
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Token(kind) => write!(f, "`{}`", kind),
+            Expected::Expression => write!(f, "expression"),
+        }
+    }
 }
 
 impl DBError {
-    pub(crate) fn print(&self, rs: &RawStatement) {
+    /// The part of the source the error points at
+    pub fn span(&self) -> Span {
         use DBError::*;
 
-        let message = match self {
-            IdentExpected(token) => format!("Identifier expected, found {}", rs.slice(&token.span)),
-            KeywordExpected(token) => format!("Keyword expected, found {}", rs.slice(&token.span)),
+        match self {
+            UnTerminatedString(span)
+            | UnTerminatedComment(span)
+            | UnTerminatedIdentifier(span)
+            | EmptyIdentifier(span)
+            | UnterminatedFloat(span)
+            | IntegerOutOfRange(span)
+            | FloatOutOfRange(span)
+            | MalformedNumber(span)
+            | IllegalCharacter(_, span)
+            | UnexpectedEof { span, .. }
+            | ScaleExceedsPrecision { span, .. }
+            | ColumnCountMismatch { span, .. }
+            | InvalidFrame { span, .. }
+            | Eof(span) => *span,
+            Unexpected { found: token, .. }
+            | UnexpectedToken { found: token }
+            | IdentExpected(token)
+            | DatatypeExpected(token)
+            | InvalidTypeParameter(token)
+            | DateTimeFieldExpected(token)
+            | UnexpectedStatement(token) => token.span,
+        }
+    }
+
+    /// What went wrong, tokens are described from what the lexer made of them
+    pub fn message(&self) -> String {
+        self.describe(None)
+    }
+
+    /// The source is used to quote tokens as they were written
+    pub(crate) fn describe(&self, rs: Option<&RawStatement>) -> String {
+        use DBError::*;
+
+        match self {
+            IdentExpected(token) => format!("Identifier expected, found {}", text(token, rs)),
             DatatypeExpected(token) => {
-                format!("Datatype expected, found {}", rs.slice(&token.span))
+                format!("Datatype expected, found {}", text(token, rs))
             }
+            DateTimeFieldExpected(token) => format!(
+                "Date or time field such as YEAR expected, found {}",
+                text(token, rs)
+            ),
+            InvalidTypeParameter(token) => format!(
+                "Type parameter must be a positive integer, found {}",
+                text(token, rs)
+            ),
+            ScaleExceedsPrecision {
+                scale, precision, ..
+            } => format!(
+                "Scale {} must not exceed the precision {}",
+                scale, precision
+            ),
             Unexpected { found, expected } => format!(
-                "Unexpected token: found {}, expected {}",
-                rs.slice(&found.span),
-                expected
+                "Unexpected token: found {}, {}",
+                text(found, rs),
+                describe_expected(expected)
             ),
-            UnexpectedEof { expected } => format!("Unexpected end of file: expected {}", expected),
+            UnexpectedEof { expected, .. } if expected.is_empty() => {
+                "Unexpected end of file".to_string()
+            }
+            UnexpectedEof { expected, .. } => {
+                format!("Unexpected end of file: {}", describe_expected(expected))
+            }
             UnexpectedToken { found } => {
-                format!("Unexpected token: found {}", rs.slice(&found.span))
+                format!("Unexpected token: found {}", text(found, rs))
+            }
+            UnTerminatedString(_) => "Unterminated string".to_string(),
+            UnTerminatedIdentifier(_) => "Unterminated quoted identifier".to_string(),
+            EmptyIdentifier(_) => "Empty quoted identifier".to_string(),
+            UnTerminatedComment(_) => "Unterminated block comment".to_string(),
+            UnterminatedFloat(span) => match rs {
+                Some(rs) => format!("Unterminated Float '{}'", rs.slice(span)),
+                None => "Unterminated Float".to_string(),
+            },
+            IntegerOutOfRange(span) => match rs {
+                Some(rs) => format!("Integer '{}' is out of range", rs.slice(span)),
+                None => "Integer is out of range".to_string(),
+            },
+            FloatOutOfRange(span) => match rs {
+                Some(rs) => format!("Float '{}' is out of range", rs.slice(span)),
+                None => "Float is out of range".to_string(),
+            },
+            MalformedNumber(span) => match rs {
+                Some(rs) => format!("Malformed number '{}'", rs.slice(span)),
+                None => "Malformed number".to_string(),
+            },
+            IllegalCharacter(c, _) => format!("Illegal character '{}'", c),
+            ColumnCountMismatch {
+                operator,
+                left,
+                right,
+                ..
+            } => format!(
+                "Each {} query must have the same number of columns, found {} and {}",
+                operator, left, right
+            ),
+            InvalidFrame { reason, .. } => match reason {
+                FrameError::StartsUnboundedFollowing => {
+                    "Window frame cannot start at UNBOUNDED FOLLOWING".to_string()
+                }
+                FrameError::EndsUnboundedPreceding => {
+                    "Window frame cannot end at UNBOUNDED PRECEDING".to_string()
+                }
+                FrameError::StartsAfterEnd => "Window frame starts after its end".to_string(),
+            },
+            UnexpectedStatement(token) => {
+                format!("Unexpected statement {}", text(token, rs))
+            }
+            Eof(_) => "End of file reached unexpectedly".to_string(),
+        }
+    }
+
+    /// A hint on how to fix the error, when there is a common one
+    pub(crate) fn help(&self) -> Option<String> {
+        use DBError::*;
+
+        let help = match self {
+            UnTerminatedString(_) => "close the string with `'`, a quote inside is written as `''`",
+            UnTerminatedIdentifier(_) => "close the identifier with the quote that opened it",
+            UnTerminatedComment(_) => "close the comment with `*/`, comments may nest",
+            EmptyIdentifier(_) => "quoted identifiers need at least one character",
+            UnterminatedFloat(_) => "add digits after the decimal point, as in `1.0`",
+            IntegerOutOfRange(_) => {
+                "integers must fit in 64 bits, write it as a float such as `1.0` if needed"
+            }
+            FloatOutOfRange(_) => "floats must fit in 64 bits",
+            MalformedNumber(_) => {
+                "an exponent needs digits as in `1e10`, and a name needs a space before it"
+            }
+            ScaleExceedsPrecision { .. } => {
+                "the scale counts the digits after the decimal point, which are part of the precision"
+            }
+            ColumnCountMismatch { .. } => {
+                "list the same number of columns in the queries on both sides"
             }
-            UnTerminatedString(pos) => {
-                format!("Unterminated string at {}", pos.start)
+            InvalidFrame { .. } => {
+                "bounds go UNBOUNDED PRECEDING, n PRECEDING, CURRENT ROW, n FOLLOWING, UNBOUNDED FOLLOWING, and a lone start ends at CURRENT ROW"
             }
-            UnterminatedFloat(span) => format!("Unterminated Float '{}'", rs.slice(span)),
-            IllegalCharacter(c, pos) => format!("Illegal character '{}' at {}", c, pos),
-            UnexpectedStatement => "Unexpected statement".to_string(),
-            Eof => "End of file reached unexpectedly".to_string(),
-            UnexpectedKeyword { found, allowed } => {
-                let allowed_keywords: Vec<String> = allowed.iter().map(|k| k.to_string()).collect();
-                format!(
-                    "Unexpected keyword '{}'. Allowed keywords are: {}",
-                    found,
-                    allowed_keywords.join(", ")
-                )
+            UnexpectedStatement(_) => {
+                "statements start with CREATE, ALTER, DROP, INSERT, SELECT, UPDATE, DELETE, WITH, USE or TRUNCATE"
             }
+            UnexpectedEof { expected, .. }
+                if expected.contains(&Expected::Token(TokenKind::Symbol(Symbol::Semicolon))) =>
+            {
+                "end every statement with `;`"
+            }
+            _ => return None,
         };
+        Some(help.to_string())
+    }
+}
+
+impl Display for DBError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.message(), self.span().start)
+    }
+}
+
+impl std::error::Error for DBError {}
+
+/// The text of a token, as written when the source is at hand
+fn text(token: &Token, rs: Option<&RawStatement>) -> String {
+    match (rs, &token.kind) {
+        (Some(rs), _) => rs.slice(&token.span).to_string(),
+        (None, TokenKind::Ident { value, .. }) => value.clone(),
+        (None, kind) => kind.to_string(),
+    }
+}
+
+/// Lists what was expected as "expected `a`" or "expected one of `a` `b`"
+fn describe_expected(expected: &[Expected]) -> String {
+    let tokens: Vec<String> = expected.iter().map(|item| item.to_string()).collect();
+    match tokens.as_slice() {
+        [token] => format!("expected {}", token),
+        tokens => format!("expected one of {}", tokens.join(" ")),
+    }
+}
+
+/// Everything that went wrong while parsing a source, along with the source so the errors
+/// can point into it
+#[derive(Debug, Clone)]
+pub struct ParseErrors {
+    source: RawStatement,
+    file_name: Option<String>,
+    errors: Vec<DBError>,
+}
+
+impl ParseErrors {
+    pub(crate) fn new(source: &str, errors: Vec<DBError>) -> Self {
+        Self {
+            source: RawStatement::new(source),
+            file_name: None,
+            errors,
+        }
+    }
+
+    /// Errors of a single statement, rendered against that statement alone
+    pub(crate) fn for_statement(statement: RawStatement, errors: Vec<DBError>) -> Self {
+        Self {
+            source: statement,
+            file_name: None,
+            errors,
+        }
+    }
+
+    /// Names the file the source was read from in the rendered errors
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    pub fn errors(&self) -> &[DBError] {
+        &self.errors
+    }
+
+    /// Prints every error to stderr, colored when it is a terminal
+    pub fn print(&self) {
+        let color = io::stderr().is_terminal();
+        for error in &self.errors {
+            eprint!("{}", self.diagnostic(error).color(color));
+        }
+    }
+
+    fn diagnostic<'a>(&'a self, error: &'a DBError) -> Diagnostic<'a> {
+        Diagnostic::new(error, &self.source, self.file_name.as_deref())
+    }
+}
+
+impl Display for ParseErrors {
+    /// Renders every error with the part of the source it points at
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for error in &self.errors {
+            write!(f, "{}", self.diagnostic(error))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseErrors {}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    #[test]
+    fn errors_expose_message_and_span() {
+        let errors = parse("SELECT a\nFROM t WHERE;").unwrap_err();
+        let [error] = errors.errors() else {
+            panic!("{}", errors);
+        };
+        assert_eq!(
+            error.message(),
+            "Unexpected token: found ;, expected expression"
+        );
+        assert_eq!(error.span().start.to_string(), "2:13");
+        assert_eq!(
+            error.to_string(),
+            "Unexpected token: found ;, expected expression at 2:13"
+        );
+    }
 
-        eprintln!("Error: {}", message);
+    #[test]
+    fn rendering_quotes_tokens_as_written() {
+        let errors = parse("SELECT a FROM t WHERE a = 1 \"B\";").unwrap_err();
+        assert_eq!(
+            errors.errors()[0].message(),
+            "Unexpected token: found B, expected one of `GROUP` `HAVING` `WINDOW` `INTERSECT` `UNION` `EXCEPT` `ORDER` `LIMIT` `OFFSET` `;`"
+        );
+        assert!(errors
+            .to_string()
+            .starts_with("error: Unexpected token: found \"B\", expected one of"));
     }
 }
@@ -1,13 +1,13 @@
 use std::fmt::Display;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub(crate) enum Keyword {
+pub enum Keyword {
 
     Database,
     Table,
 
     Create,
-    // Alter,
+    Alter,
     Drop,
     Insert,
     Update,
@@ -19,6 +19,18 @@ pub(crate) enum Keyword {
     String,
     Boolean,
     DateTime,
+    SmallInt,
+    BigInt,
+    Double,
+    Decimal,
+    Char,
+    Varchar,
+    Date,
+    Time,
+    Timestamp,
+    Blob,
+    Bytes,
+    Uuid,
     Add,
     Modify,
     Rename,
@@ -33,26 +45,155 @@ pub(crate) enum Keyword {
     And,
     Or,
     Set,
+    Constraint,
+    Primary,
+    Key,
+    Unique,
+    Check,
+    Foreign,
+    References,
+    Null,
+    Default,
+    Is,
+    Distinct,
+    Coalesce,
+    NullIf,
+    As,
+    All,
+    Group,
+    By,
+    Having,
+    Order,
+    Asc,
+    Desc,
+    Nulls,
+    First,
+    Last,
+    Limit,
+    Offset,
+    Join,
+    Inner,
+    Left,
+    Right,
+    Full,
+    Outer,
+    Cross,
+    On,
+    Using,
+    Exists,
+    Any,
+    Some,
+    Union,
+    Intersect,
+    Except,
+    With,
+    Recursive,
+    Case,
+    When,
+    Then,
+    Else,
+    End,
+    Cast,
+    Between,
+    Extract,
+    Over,
+    Partition,
+    Window,
+    Rows,
+    Range,
+    Unbounded,
+    Preceding,
+    Following,
+    Current,
+    Row,
+    If,
+    Cascade,
+    Use,
+    Truncate,
 }
 
 impl Keyword {
+    /// Non-reserved keywords only mean something in a few places and are names anywhere
+    /// else, the lexer hands them over as identifiers
+    pub(crate) const fn is_reserved(&self) -> bool {
+        !matches!(
+            self,
+            Keyword::Key
+                // only keywords after ORDER BY and LIMIT
+                | Keyword::Nulls
+                | Keyword::First
+                | Keyword::Last
+                | Keyword::Offset
+                // join types, only keywords before JOIN
+                | Keyword::Left
+                | Keyword::Right
+                | Keyword::Full
+                // window frames, only keywords inside OVER (...) and WINDOW
+                | Keyword::Rows
+                | Keyword::Range
+                | Keyword::Row
+                | Keyword::Current
+                | Keyword::Unbounded
+                | Keyword::Preceding
+                | Keyword::Following
+                | Keyword::Partition
+                // only a keyword after a function call
+                | Keyword::Over
+                // only keywords in DROP, CREATE and at the start of a statement
+                | Keyword::If
+                | Keyword::Cascade
+                | Keyword::Use
+                | Keyword::Truncate
+                // type names
+                | Keyword::SmallInt
+                | Keyword::Int
+                | Keyword::BigInt
+                | Keyword::Float
+                | Keyword::Double
+                | Keyword::Decimal
+                | Keyword::Char
+                | Keyword::Varchar
+                | Keyword::String
+                | Keyword::Boolean
+                | Keyword::Date
+                | Keyword::Time
+                | Keyword::Timestamp
+                | Keyword::DateTime
+                | Keyword::Blob
+                | Keyword::Bytes
+                | Keyword::Uuid
+        )
+    }
+
     pub(crate) fn get_keyword_kind(keyword: &str) -> Option<Keyword> {
         let keyword = match keyword.to_lowercase().as_str() {
             "create" => Keyword::Create,
             "database" => Keyword::Database,
             "table" => Keyword::Table,
-            // "alter" => Keyword::Alter,
+            "alter" => Keyword::Alter,
             "drop" => Keyword::Drop,
             "insert" => Keyword::Insert,
             "update" => Keyword::Update,
             "delete" => Keyword::Delete,
             "select" => Keyword::Select,
             "from" => Keyword::From,
-            "int" => Keyword::Int,
+            "int" | "integer" => Keyword::Int,
             "float" => Keyword::Float,
             "string" => Keyword::String,
             "boolean" => Keyword::Boolean,
             "datetime" => Keyword::DateTime,
+            "smallint" => Keyword::SmallInt,
+            "bigint" => Keyword::BigInt,
+            "double" => Keyword::Double,
+            "decimal" => Keyword::Decimal,
+            "char" => Keyword::Char,
+            "varchar" => Keyword::Varchar,
+            "date" => Keyword::Date,
+            "time" => Keyword::Time,
+            "timestamp" => Keyword::Timestamp,
+            "blob" => Keyword::Blob,
+            "bytes" => Keyword::Bytes,
+            "uuid" => Keyword::Uuid,
             "add" => Keyword::Add,
             "modify" => Keyword::Modify,
             "rename" => Keyword::Rename,
@@ -67,6 +208,71 @@ impl Keyword {
             "and" => Keyword::And,
             "or" => Keyword::Or,
             "set" => Keyword::Set,
+            "constraint" => Keyword::Constraint,
+            "primary" => Keyword::Primary,
+            "key" => Keyword::Key,
+            "unique" => Keyword::Unique,
+            "check" => Keyword::Check,
+            "foreign" => Keyword::Foreign,
+            "references" => Keyword::References,
+            "null" => Keyword::Null,
+            "default" => Keyword::Default,
+            "is" => Keyword::Is,
+            "distinct" => Keyword::Distinct,
+            "coalesce" => Keyword::Coalesce,
+            "nullif" => Keyword::NullIf,
+            "as" => Keyword::As,
+            "all" => Keyword::All,
+            "group" => Keyword::Group,
+            "by" => Keyword::By,
+            "having" => Keyword::Having,
+            "order" => Keyword::Order,
+            "asc" => Keyword::Asc,
+            "desc" => Keyword::Desc,
+            "nulls" => Keyword::Nulls,
+            "first" => Keyword::First,
+            "last" => Keyword::Last,
+            "limit" => Keyword::Limit,
+            "offset" => Keyword::Offset,
+            "join" => Keyword::Join,
+            "inner" => Keyword::Inner,
+            "left" => Keyword::Left,
+            "right" => Keyword::Right,
+            "full" => Keyword::Full,
+            "outer" => Keyword::Outer,
+            "cross" => Keyword::Cross,
+            "on" => Keyword::On,
+            "using" => Keyword::Using,
+            "exists" => Keyword::Exists,
+            "any" => Keyword::Any,
+            "some" => Keyword::Some,
+            "union" => Keyword::Union,
+            "intersect" => Keyword::Intersect,
+            "except" => Keyword::Except,
+            "with" => Keyword::With,
+            "recursive" => Keyword::Recursive,
+            "case" => Keyword::Case,
+            "when" => Keyword::When,
+            "then" => Keyword::Then,
+            "else" => Keyword::Else,
+            "end" => Keyword::End,
+            "cast" => Keyword::Cast,
+            "between" => Keyword::Between,
+            "extract" => Keyword::Extract,
+            "over" => Keyword::Over,
+            "partition" => Keyword::Partition,
+            "window" => Keyword::Window,
+            "rows" => Keyword::Rows,
+            "range" => Keyword::Range,
+            "unbounded" => Keyword::Unbounded,
+            "preceding" => Keyword::Preceding,
+            "following" => Keyword::Following,
+            "current" => Keyword::Current,
+            "row" => Keyword::Row,
+            "if" => Keyword::If,
+            "cascade" => Keyword::Cascade,
+            "use" => Keyword::Use,
+            "truncate" => Keyword::Truncate,
             _ => return None,
         };
         Some(keyword)
@@ -80,7 +286,7 @@ impl Display for Keyword {
             Keyword::Database => "DATABASE",
 
             Keyword::Create => "CREATE",
-            // Keyword::Alter => "ALTER",
+            Keyword::Alter => "ALTER",
             Keyword::Drop => "DROP",
             Keyword::Insert => "INSERT",
             Keyword::Update => "UPDATE",
@@ -92,6 +298,18 @@ impl Display for Keyword {
             Keyword::String => "STRING",
             Keyword::Boolean => "BOOLEAN",
             Keyword::DateTime => "DATETIME",
+            Keyword::SmallInt => "SMALLINT",
+            Keyword::BigInt => "BIGINT",
+            Keyword::Double => "DOUBLE",
+            Keyword::Decimal => "DECIMAL",
+            Keyword::Char => "CHAR",
+            Keyword::Varchar => "VARCHAR",
+            Keyword::Date => "DATE",
+            Keyword::Time => "TIME",
+            Keyword::Timestamp => "TIMESTAMP",
+            Keyword::Blob => "BLOB",
+            Keyword::Bytes => "BYTES",
+            Keyword::Uuid => "UUID",
             Keyword::Add => "ADD",
             Keyword::Modify => "MODIFY",
             Keyword::Rename => "RENAME",
@@ -106,6 +324,71 @@ impl Display for Keyword {
             Keyword::And => "AND",
             Keyword::Or => "OR",
             Keyword::Set => "SET",
+            Keyword::Constraint => "CONSTRAINT",
+            Keyword::Primary => "PRIMARY",
+            Keyword::Key => "KEY",
+            Keyword::Unique => "UNIQUE",
+            Keyword::Check => "CHECK",
+            Keyword::Foreign => "FOREIGN",
+            Keyword::References => "REFERENCES",
+            Keyword::Null => "NULL",
+            Keyword::Default => "DEFAULT",
+            Keyword::Is => "IS",
+            Keyword::Distinct => "DISTINCT",
+            Keyword::Coalesce => "COALESCE",
+            Keyword::NullIf => "NULLIF",
+            Keyword::As => "AS",
+            Keyword::All => "ALL",
+            Keyword::Group => "GROUP",
+            Keyword::By => "BY",
+            Keyword::Having => "HAVING",
+            Keyword::Order => "ORDER",
+            Keyword::Asc => "ASC",
+            Keyword::Desc => "DESC",
+            Keyword::Nulls => "NULLS",
+            Keyword::First => "FIRST",
+            Keyword::Last => "LAST",
+            Keyword::Limit => "LIMIT",
+            Keyword::Offset => "OFFSET",
+            Keyword::Join => "JOIN",
+            Keyword::Inner => "INNER",
+            Keyword::Left => "LEFT",
+            Keyword::Right => "RIGHT",
+            Keyword::Full => "FULL",
+            Keyword::Outer => "OUTER",
+            Keyword::Cross => "CROSS",
+            Keyword::On => "ON",
+            Keyword::Using => "USING",
+            Keyword::Exists => "EXISTS",
+            Keyword::Any => "ANY",
+            Keyword::Some => "SOME",
+            Keyword::Union => "UNION",
+            Keyword::Intersect => "INTERSECT",
+            Keyword::Except => "EXCEPT",
+            Keyword::With => "WITH",
+            Keyword::Recursive => "RECURSIVE",
+            Keyword::Case => "CASE",
+            Keyword::When => "WHEN",
+            Keyword::Then => "THEN",
+            Keyword::Else => "ELSE",
+            Keyword::End => "END",
+            Keyword::Cast => "CAST",
+            Keyword::Between => "BETWEEN",
+            Keyword::Extract => "EXTRACT",
+            Keyword::Over => "OVER",
+            Keyword::Partition => "PARTITION",
+            Keyword::Window => "WINDOW",
+            Keyword::Rows => "ROWS",
+            Keyword::Range => "RANGE",
+            Keyword::Unbounded => "UNBOUNDED",
+            Keyword::Preceding => "PRECEDING",
+            Keyword::Following => "FOLLOWING",
+            Keyword::Current => "CURRENT",
+            Keyword::Row => "ROW",
+            Keyword::If => "IF",
+            Keyword::Cascade => "CASCADE",
+            Keyword::Use => "USE",
+            Keyword::Truncate => "TRUNCATE",
         };
         write!(f, "{keyword_str}")
     }
@@ -1,30 +1,38 @@
 use std::fmt::Display;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum LiteralType {
-    Boolean,
-    Integer,
-    Float,
-    String,
+/// A literal value, already decoded from its source representation
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
 }
 
-impl LiteralType {
-    pub(crate) fn get_literal(keyword: &str) -> Option<LiteralType> {
-        let keyword = match keyword.to_lowercase().as_str() {
-            "true" | "false" => LiteralType::Boolean,
+impl Literal {
+    pub(crate) fn get_literal(keyword: &str) -> Option<Literal> {
+        let literal = match keyword.to_lowercase().as_str() {
+            "true" => Literal::Boolean(true),
+            "false" => Literal::Boolean(false),
             _ => return None,
         };
-        Some(keyword)
+        Some(literal)
     }
 }
 
-impl Display for LiteralType {
+impl Display for Literal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            LiteralType::Boolean => write!(f, "Boolean"),
-            LiteralType::Integer => write!(f, "Integer"),
-            LiteralType::Float => write!(f, "Float"),
-            LiteralType::String => write!(f, "String"),
+            Literal::Null => write!(f, "NULL"),
+            Literal::Boolean(true) => write!(f, "TRUE"),
+            Literal::Boolean(false) => write!(f, "FALSE"),
+            Literal::Integer(value) => write!(f, "{}", value),
+            // written without an exponent, with the fractional part that keeps them floats;
+            // the lexer rejects infinite floats, so the digits always read back
+            Literal::Float(value) if value.fract() == 0.0 => write!(f, "{}.0", value),
+            Literal::Float(value) => write!(f, "{}", value),
+            Literal::String(value) => write!(f, "'{}'", value.replace('\'', "''")),
         }
     }
 }
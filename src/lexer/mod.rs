@@ -1,7 +1,7 @@
-pub(crate) mod keyword;
-pub(crate) mod literal;
-pub(crate) mod symbol;
-pub(crate) mod token;
+pub mod keyword;
+pub mod literal;
+pub mod symbol;
+pub mod token;
 
 use crate::{
     common::{
@@ -11,10 +11,10 @@ use crate::{
     error::DBError, source::Atom,
 };
 use keyword::Keyword;
-use literal::LiteralType;
+use literal::Literal;
 use std::iter::Peekable;
 use symbol::Symbol;
-pub(crate) use token::Token;
+pub use token::Token;
 use token::TokenKind;
 
 pub(crate) struct Lexer<Characters>
@@ -51,20 +51,37 @@ where
 
                 ',' => Token::from_symbol(Symbol::Comma, ch.position),
 
+                '.' => Token::from_symbol(Symbol::Dot, ch.position),
+
+                ':' if self.if_next(':') => Token::from_symbol(Symbol::DoubleColon, ch.position),
+
                 ';' => Token::from_symbol(Symbol::Semicolon, ch.position),
 
                 '+' if self.if_next('=') => Token::from_symbol(Symbol::PlusEquals, ch.position),
 
                 '+' => Token::from_symbol(Symbol::Plus, ch.position),
 
+                // skip comments
+                '-' if self.if_next('-') => {
+                    self.skip_line();
+                    continue;
+                }
+
                 '-' if self.if_next('=') => Token::from_symbol(Symbol::MinusEquals, ch.position),
 
                 '-' => Token::from_symbol(Symbol::Minus, ch.position),
 
+                '*' if self.if_next('*') => Token::from_symbol(Symbol::DoubleStar, ch.position),
+
                 '*' if self.if_next('=') => Token::from_symbol(Symbol::StarEquals, ch.position),
 
                 '*' => Token::from_symbol(Symbol::Star, ch.position),
 
+                '/' if self.if_next('*') => match self.skip_block_comment(ch) {
+                    Ok(()) => continue,
+                    Err(err) => return Some(Err(err)),
+                },
+
                 '/' if self.if_next('=') => Token::from_symbol(Symbol::DivideEquals, ch.position),
 
                 '/' => Token::from_symbol(Symbol::Divide, ch.position),
@@ -84,11 +101,17 @@ where
                 '<' if self.if_next('=') => {
                     Token::from_symbol(Symbol::LessThanOrEquals, ch.position)
                 }
+                '<' if self.if_next('<') => Token::from_symbol(Symbol::ShiftLeft, ch.position),
+
+                '<' if self.if_next('>') => Token::from_symbol(Symbol::NotEquals, ch.position),
+
                 '<' => Token::from_symbol(Symbol::LessThan, ch.position),
 
                 '>' if self.if_next('=') => {
                     Token::from_symbol(Symbol::GreaterThanOrEquals, ch.position)
                 }
+                '>' if self.if_next('>') => Token::from_symbol(Symbol::ShiftRight, ch.position),
+
                 '>' => Token::from_symbol(Symbol::GreaterThan, ch.position),
 
                 '&' if self.if_next('&') => Token::from_symbol(Symbol::And, ch.position),
@@ -103,14 +126,25 @@ where
 
                 '~' => Token::from_symbol(Symbol::BitNot, ch.position),
 
-                '\"' | '\'' => match self.consume_string(ch) {
+                '\'' => match self.consume_string(ch, false) {
+                    Ok(token) => token,
+                    err => return Some(err),
+                },
+
+                // quoted identifiers
+                '\"' | '`' => match self.consume_quoted_identifier(ch) {
+                    Ok(token) => token,
+                    err => return Some(err),
+                },
+
+                // E'...' strings understand backslash escapes
+                'e' | 'E' if self.if_next('\'') => match self.consume_string(ch, true) {
                     Ok(token) => token,
                     err => return Some(err),
                 },
 
-                // skip comments
                 '#' => {
-                    self.atoms.find(|ch| ch.value != '\n');
+                    self.skip_line();
                     continue;
                 }
 
@@ -123,7 +157,11 @@ where
                 ch_val if Self::is_valid_ident(ch_val) => self.consume_identifier(ch),
 
                 value => {
-                    return Some(Err(DBError::IllegalCharacter(value, ch.position)));
+                    let span = Span {
+                        start: ch.position,
+                        end: ch.position,
+                    };
+                    return Some(Err(DBError::IllegalCharacter(value, span)));
                 }
             };
 
@@ -151,54 +189,204 @@ where
         self.atoms.next()
     }
 
-    fn consume_numbers(&mut self, initial_char: Atom) -> Position {
+    /// Skips the rest of a line comment, including the line break
+    fn skip_line(&mut self) {
+        for atom in self.atoms.by_ref() {
+            if atom.value == '\n' {
+                break;
+            }
+        }
+    }
+
+    /// Skips a `/* ... */` comment whose opening `/` is `start`, nested comments included
+    fn skip_block_comment(&mut self, start: Atom) -> Result<(), DBError> {
+        let mut depth = 1;
+        let mut span = Span {
+            start: start.position,
+            end: start.position,
+        };
+
+        while let Some(atom) = self.next_atom() {
+            span.end = atom.position;
+            match atom.value {
+                '/' if self.if_next('*') => depth += 1,
+                '*' if self.if_next('/') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Err(DBError::UnTerminatedComment(span))
+    }
+
+    fn consume_numbers(&mut self, initial_char: Atom, text: &mut String) -> Position {
         let mut last_position = initial_char.position;
+        text.push(initial_char.value);
 
         while let Some(next_ch) = self
             .atoms
             .consume_if(|next_ch| next_ch.value.is_ascii_digit())
         {
+            text.push(next_ch.value);
             last_position = next_ch.position;
         }
 
         last_position
     }
 
-    pub(crate) fn consume_string(&mut self, enclosing: Atom) -> Result<Token, DBError> {
-        self.atoms
-            .consume_while(|next_ch| next_ch.value != enclosing.value && next_ch.value != '\n');
+    /// Consumes a string literal up to its closing quote, which may be on a later line.
+    ///
+    /// A doubled quote (`'it''s'`) stands for a single one. When `backslash_escapes`
+    /// is set, C style escapes such as `\n` are decoded as well.
+    pub(crate) fn consume_string(
+        &mut self,
+        start: Atom,
+        backslash_escapes: bool,
+    ) -> Result<Token, DBError> {
+        match self.consume_quoted(start, '\'', backslash_escapes) {
+            Ok((value, span)) => Ok(Token::new(TokenKind::Literal(Literal::String(value)), span)),
+            Err(span) => Err(DBError::UnTerminatedString(span)),
+        }
+    }
+
+    /// Consumes a `"quoted"` or `` `quoted` `` identifier, keeping its case as written.
+    /// A doubled quote stands for a single one.
+    pub(crate) fn consume_quoted_identifier(&mut self, enclosing: Atom) -> Result<Token, DBError> {
+        match self.consume_quoted(enclosing, enclosing.value, false) {
+            Ok((value, span)) if value.is_empty() => Err(DBError::EmptyIdentifier(span)),
+            Ok((value, span)) => Ok(Token::new(
+                TokenKind::Ident {
+                    value,
+                    quoted: true,
+                },
+                span,
+            )),
+            Err(span) => Err(DBError::UnTerminatedIdentifier(span)),
+        }
+    }
+
+    /// Collects the decoded content up to the closing `enclosing` character.
+    /// Fails with the span consumed so far if the source ends first.
+    fn consume_quoted(
+        &mut self,
+        start: Atom,
+        enclosing: char,
+        backslash_escapes: bool,
+    ) -> Result<(String, Span), Span> {
+        let mut value = String::new();
         let mut span = Span {
-            start: enclosing.position,
-            end: enclosing.position,
+            start: start.position,
+            end: start.position,
         };
-        if let Some(last) = self.atoms.consume_if(|ch| ch.value == enclosing.value) {
-            span.end = last.position;
-            return Ok(Token::new(TokenKind::Literal(LiteralType::String), span));
+
+        while let Some(atom) = self.next_atom() {
+            span.end = atom.position;
+            match atom.value {
+                ch if ch == enclosing => {
+                    let Some(escaped) = self.atoms.consume_if(|next| next.value == enclosing)
+                    else {
+                        return Ok((value, span));
+                    };
+                    span.end = escaped.position;
+                    value.push(enclosing);
+                }
+                '\\' if backslash_escapes => {
+                    let Some(escaped) = self.next_atom() else {
+                        break;
+                    };
+                    span.end = escaped.position;
+                    value.push(match escaped.value {
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        '0' => '\0',
+                        // any other character, including quotes and `\`, stands for itself
+                        other => other,
+                    });
+                }
+                ch => value.push(ch),
+            }
         }
+
         // If we reach here, it means we didn't find a closing quote.
-        Err(DBError::UnTerminatedString(span))
+        Err(span)
     }
 
     pub(crate) fn consume_numeric_literal(&mut self, initial_char: Atom) -> Result<Token, DBError> {
+        let mut text = String::new();
         let mut span = Span {
             start: initial_char.position,
-            end: self.consume_numbers(initial_char),
+            end: self.consume_numbers(initial_char, &mut text),
         };
+        let mut is_float = false;
 
-        let token = if let Some(next_ch) = self.atoms.consume_if(|ch| ch.value == '.') {
+        if let Some(next_ch) = self.atoms.consume_if(|ch| ch.value == '.') {
             // Collect the fractional part of the number.
-            span.end = self.consume_numbers(next_ch);
+            span.end = self.consume_numbers(next_ch, &mut text);
             if span.end == next_ch.position {
                 // If the fractional part is just a single character, it's invalid.
                 return Err(DBError::UnterminatedFloat(span));
             }
+            is_float = true;
+        }
 
-            Token::new(TokenKind::Literal(LiteralType::Float), span)
+        // an exponent, `[eE][+-]digits`, makes a float of any number
+        if let Some(next_ch) = self.atoms.consume_if(|ch| matches!(ch.value, 'e' | 'E')) {
+            text.push(next_ch.value);
+            span.end = next_ch.position;
+            if let Some(sign) = self.atoms.consume_if(|ch| matches!(ch.value, '+' | '-')) {
+                text.push(sign.value);
+                span.end = sign.position;
+            }
+            match self.atoms.consume_if(|ch| ch.value.is_ascii_digit()) {
+                Some(digit) => span.end = self.consume_numbers(digit, &mut text),
+                None => return Err(self.malformed_number(span)),
+            }
+            is_float = true;
+        }
+
+        // a name can't start right after a number, `1abc` is a typo rather than `1 AS abc`
+        if self
+            .atoms
+            .peek()
+            .is_some_and(|ch| Self::is_valid_ident(ch.value))
+        {
+            return Err(self.malformed_number(span));
+        }
+
+        let literal = if is_float {
+            // digits around a single dot and an exponent always form a valid float
+            let value: f64 = text.parse().expect("float literal is malformed");
+            if value.is_infinite() {
+                return Err(DBError::FloatOutOfRange(span));
+            }
+            Literal::Float(value)
         } else {
-            Token::new(TokenKind::Literal(LiteralType::Integer), span)
+            // 2^63 only fits negated, it is carried as i64::MIN and the parser checks
+            // for the minus in front of it
+            let value = match text.parse::<i64>() {
+                Ok(value) => value,
+                Err(_) if text.parse::<u64>() == Ok(i64::MIN.unsigned_abs()) => i64::MIN,
+                Err(_) => return Err(DBError::IntegerOutOfRange(span)),
+            };
+            Literal::Integer(value)
         };
 
-        Ok(token)
+        Ok(Token::new(TokenKind::Literal(literal), span))
+    }
+
+    /// Covers the rest of a malformed number, up to where a name would end
+    fn malformed_number(&mut self, mut span: Span) -> DBError {
+        while let Some(next_ch) = self.atoms.consume_if(|ch| Self::is_valid_ident(ch.value)) {
+            span.end = next_ch.position;
+        }
+        DBError::MalformedNumber(span)
     }
 
     pub(crate) fn consume_identifier(&mut self, ch: Atom) -> Token {
@@ -218,12 +406,18 @@ where
             end: last.position,
         };
 
-        if let Some(keyword) = Keyword::get_keyword_kind(&word) {
+        if let Some(keyword) = Keyword::get_keyword_kind(&word).filter(Keyword::is_reserved) {
             Token::new(TokenKind::Keyword(keyword), span)
-        } else if let Some(literal) = LiteralType::get_literal(&word) {
+        } else if let Some(literal) = Literal::get_literal(&word) {
             Token::new(TokenKind::Literal(literal), span)
         } else {
-            Token::new(TokenKind::Ident, span)
+            Token::new(
+                TokenKind::Ident {
+                    value: word,
+                    quoted: false,
+                },
+                span,
+            )
         }
     }
 
@@ -231,3 +425,189 @@ where
         ch.is_ascii_alphabetic() || ch == '_' || ch.is_ascii_digit()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        keyword::Keyword,
+        literal::Literal,
+        symbol::Symbol,
+        token::{Token, TokenKind},
+    };
+    use crate::{common::position::Span, error::DBError, tokenize};
+
+    fn kinds(sql: &str) -> Vec<TokenKind> {
+        tokenize(sql).map(|token| token.unwrap().kind).collect()
+    }
+
+    fn ident(name: &str) -> TokenKind {
+        TokenKind::Ident {
+            value: name.to_string(),
+            quoted: false,
+        }
+    }
+
+    fn quoted(name: &str) -> TokenKind {
+        TokenKind::Ident {
+            value: name.to_string(),
+            quoted: true,
+        }
+    }
+
+    /// The first error lexing the source runs into
+    fn error(sql: &str) -> DBError {
+        tokenize(sql).find_map(Result::err).unwrap()
+    }
+
+    #[test]
+    fn keywords_are_case_insensitive() {
+        assert_eq!(
+            kinds("select From wHeRe"),
+            [
+                TokenKind::Keyword(Keyword::Select),
+                TokenKind::Keyword(Keyword::From),
+                TokenKind::Keyword(Keyword::Where),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_reserved_and_quoted_keywords_are_identifiers() {
+        assert_eq!(
+            kinds("key Date rows left \"select\""),
+            [
+                ident("key"),
+                ident("Date"),
+                ident("rows"),
+                ident("left"),
+                quoted("select"),
+            ]
+        );
+        let tokens: Vec<_> = tokenize("rows \"rows\" `rows`")
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(tokens[0].non_reserved_keyword(), Some(Keyword::Rows));
+        assert_eq!(tokens[1].non_reserved_keyword(), None);
+        assert_eq!(tokens[2].non_reserved_keyword(), None);
+
+        // the flag decides rather than the span, which tokens made up elsewhere may lack
+        let token = |kind| Token::new(kind, Span::default());
+        assert_eq!(
+            token(ident("rows")).non_reserved_keyword(),
+            Some(Keyword::Rows)
+        );
+        assert_eq!(token(quoted("rows")).non_reserved_keyword(), None);
+    }
+
+    #[test]
+    fn symbols_take_the_longest_match() {
+        assert_eq!(
+            kinds("** * ~ << >> & | ^ += <= <> != ="),
+            [
+                TokenKind::Symbol(Symbol::DoubleStar),
+                TokenKind::Symbol(Symbol::Star),
+                TokenKind::Symbol(Symbol::BitNot),
+                TokenKind::Symbol(Symbol::ShiftLeft),
+                TokenKind::Symbol(Symbol::ShiftRight),
+                TokenKind::Symbol(Symbol::BitAnd),
+                TokenKind::Symbol(Symbol::BitOr),
+                TokenKind::Symbol(Symbol::BitXor),
+                TokenKind::Symbol(Symbol::PlusEquals),
+                TokenKind::Symbol(Symbol::LessThanOrEquals),
+                TokenKind::Symbol(Symbol::NotEquals),
+                TokenKind::Symbol(Symbol::NotEquals),
+                TokenKind::Symbol(Symbol::Equal),
+            ]
+        );
+    }
+
+    #[test]
+    fn literals_unescape() {
+        assert_eq!(
+            kinds("1 2.5 'it''s' E'a\\nb\\'' 'multi\nline' null TRUE"),
+            [
+                TokenKind::Literal(Literal::Integer(1)),
+                TokenKind::Literal(Literal::Float(2.5)),
+                TokenKind::Literal(Literal::String("it's".to_string())),
+                TokenKind::Literal(Literal::String("a\nb'".to_string())),
+                TokenKind::Literal(Literal::String("multi\nline".to_string())),
+                TokenKind::Keyword(Keyword::Null),
+                TokenKind::Literal(Literal::Boolean(true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_malformed_literals() {
+        assert!(matches!(error("'abc"), DBError::UnTerminatedString(_)));
+        assert!(matches!(error("1."), DBError::UnterminatedFloat(_)));
+        assert!(matches!(
+            error("99999999999999999999"),
+            DBError::IntegerOutOfRange(_)
+        ));
+        assert!(matches!(error("a @ b"), DBError::IllegalCharacter('@', _)));
+        assert!(matches!(error("1e400"), DBError::FloatOutOfRange(_)));
+        let huge = format!("1{}.0", "0".repeat(400));
+        assert!(matches!(error(&huge), DBError::FloatOutOfRange(_)));
+        for sql in ["1e", "1.5e+", "2abc", "3_"] {
+            assert!(matches!(error(sql), DBError::MalformedNumber(_)), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn exponents_make_floats() {
+        assert_eq!(
+            kinds("1e10 1.5E-3 2e+2"),
+            [
+                TokenKind::Literal(Literal::Float(1e10)),
+                TokenKind::Literal(Literal::Float(1.5e-3)),
+                TokenKind::Literal(Literal::Float(200.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn smallest_integer_is_carried_for_the_parser() {
+        assert_eq!(
+            kinds("9223372036854775807 9223372036854775808"),
+            [
+                TokenKind::Literal(Literal::Integer(i64::MAX)),
+                TokenKind::Literal(Literal::Integer(i64::MIN)),
+            ]
+        );
+        assert!(matches!(
+            error("9223372036854775809"),
+            DBError::IntegerOutOfRange(_)
+        ));
+    }
+
+    #[test]
+    fn comments_are_skipped() {
+        assert_eq!(
+            kinds("a -- line\n/* block /* nested */ */ b # line"),
+            [ident("a"), ident("b")]
+        );
+        assert!(matches!(
+            error("a /* open /* nested */"),
+            DBError::UnTerminatedComment(_)
+        ));
+    }
+
+    #[test]
+    fn quoted_identifiers_keep_their_text() {
+        assert_eq!(
+            kinds("\"a\"\"b\" `order` \"Mixed Case\""),
+            [quoted("a\"b"), quoted("order"), quoted("Mixed Case")]
+        );
+        assert!(matches!(error("\"abc"), DBError::UnTerminatedIdentifier(_)));
+        assert!(matches!(error("`abc"), DBError::UnTerminatedIdentifier(_)));
+        assert!(matches!(error("\"\""), DBError::EmptyIdentifier(_)));
+    }
+
+    #[test]
+    fn spans_cover_the_token() {
+        let token = tokenize("SELECT\n  \"ab\"").nth(1).unwrap().unwrap();
+        assert_eq!(token.span.start.to_string(), "2:3");
+        assert_eq!(token.span.end.to_string(), "2:6");
+    }
+}
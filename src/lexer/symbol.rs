@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-pub(crate) enum Symbol {
+pub enum Symbol {
     OpenParanthesis,
     CloseParanthesis,
     OpenSquareBracket,
@@ -10,13 +10,16 @@ pub(crate) enum Symbol {
     CloseCurlyBracket,
 
     Comma,
+    Dot,
     // Colon,
+    DoubleColon,
     Semicolon,
 
     // binary
     Plus,
     Minus,
     Star,
+    DoubleStar,
     Divide,
     Percent,
 
@@ -46,7 +49,8 @@ pub(crate) enum Symbol {
     BitOr,
     BitNot,
     BitXor,
-    Carret,
+    ShiftLeft,
+    ShiftRight,
 }
 
 impl Display for Symbol {
@@ -60,6 +64,8 @@ impl Display for Symbol {
             Symbol::CloseCurlyBracket => "}",
 
             Symbol::Comma => ",",
+            Symbol::Dot => ".",
+            Symbol::DoubleColon => "::",
             Symbol::Semicolon => ";",
 
             // conditional
@@ -68,7 +74,7 @@ impl Display for Symbol {
             Symbol::Star => "*",
             Symbol::Divide => "/",
             Symbol::Percent => "%",
-            Symbol::Carret => "^",
+            Symbol::DoubleStar => "**",
 
             // assignment
             Symbol::Equal => "=",
@@ -93,6 +99,8 @@ impl Display for Symbol {
             Symbol::BitOr => "|",
             Symbol::BitNot => "~",
             Symbol::BitXor => "^",
+            Symbol::ShiftLeft => "<<",
+            Symbol::ShiftRight => ">>",
         };
         write!(f, "{}", text)
     }
@@ -1,35 +1,52 @@
 use std::fmt::Display;
 
-use crate::{common::position::Span, lexer::literal::LiteralType};
+use crate::{common::position::Span, lexer::literal::Literal};
 
 use super::{keyword::Keyword, symbol::Symbol, Position};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum TokenKind {
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
     Keyword(Keyword),
     Symbol(Symbol),
-    Literal(LiteralType),
-    Ident,
+    Literal(Literal),
+    /// `quoted` identifiers were written in quotes, which keeps them names even when they
+    /// spell a keyword
+    Ident {
+        value: String,
+        quoted: bool,
+    },
 }
 
 impl Display for TokenKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TokenKind::Keyword(k) => write!(f, "Keyword({})", k),
-            TokenKind::Symbol(s) => write!(f, "Symbol({})", s),
-            TokenKind::Literal(l) => write!(f, "Literal({})", l),
-            TokenKind::Ident => write!(f, "Identifier"),
+            TokenKind::Keyword(k) => write!(f, "{}", k),
+            TokenKind::Symbol(s) => write!(f, "{}", s),
+            TokenKind::Literal(l) => write!(f, "{}", l),
+            TokenKind::Ident { .. } => write!(f, "identifier"),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) struct Token {
-    pub(crate) kind: TokenKind,
-    pub(crate) span: Span,
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
 }
 
 impl Token {
+    /// The non-reserved keyword an unquoted identifier spells, see [`Keyword::is_reserved`]
+    pub(crate) fn non_reserved_keyword(&self) -> Option<Keyword> {
+        let TokenKind::Ident {
+            value,
+            quoted: false,
+        } = &self.kind
+        else {
+            return None;
+        };
+        Keyword::get_keyword_kind(value).filter(|keyword| !keyword.is_reserved())
+    }
+
     pub(crate) fn new(kind: TokenKind, span: Span) -> Self {
         Self { kind, span }
     }
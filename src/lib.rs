@@ -0,0 +1,96 @@
+//! A SQL lexer and parser.
+//!
+//! ```
+//! let statements = dbrs::parse("SELECT a FROM t WHERE b = 1;").unwrap();
+//! assert_eq!(statements.len(), 1);
+//! ```
+//!
+//! Statements print as canonical SQL that parses back to an equal statement.
+//! Identifiers compare by value, so their spans don't get in the way:
+//!
+//! ```
+//! let statement = &dbrs::parse("select A+b*2 from t where not (x or y);").unwrap()[0];
+//! let sql = statement.to_string();
+//! assert_eq!(sql, "SELECT A + b * 2 FROM t WHERE NOT (x OR y);");
+//! assert_eq!(&dbrs::parse(&sql).unwrap()[0], statement);
+//! ```
+//!
+//! [`parse_statements`] keeps going past a statement that fails:
+//!
+//! ```
+//! let results: Vec<_> = dbrs::parse_statements("SELECT 1; SELECT FROM; SELECT 2;").collect();
+//! assert!(results[0].is_ok() && results[1].is_err() && results[2].is_ok());
+//! ```
+
+pub mod common;
+mod diagnostic;
+pub mod error;
+pub mod lexer;
+pub mod parser;
+mod source;
+
+use std::{io::Cursor, vec};
+
+use source::{Atom, RawStatement, SplitRawStatements};
+
+pub use error::{DBError, ParseErrors};
+pub use lexer::token::{Token, TokenKind};
+pub use parser::statements::Statement;
+pub use source::StatementScanner;
+
+use {lexer::Lexer, parser::Parser};
+
+/// Parses every statement of the source, statements end with `;`.
+/// Parsing goes on after an error so that all of them are reported at once
+pub fn parse(sql: &str) -> Result<Vec<Statement>, ParseErrors> {
+    let mut statements = Vec::new();
+    let mut errors = Vec::new();
+
+    for raw_statement in Cursor::new(sql).split_raw_statements() {
+        for result in Parser::new(Lexer::new(raw_statement.iter())) {
+            match result {
+                Ok(statement) => statements.push(statement),
+                Err(statement_errors) => errors.extend(statement_errors),
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(statements)
+    } else {
+        Err(ParseErrors::new(sql, errors))
+    }
+}
+
+/// Parses the statements of the source one at a time, each with its own errors, so
+/// statements that parse are not held back by the ones that don't
+pub fn parse_statements(sql: &str) -> impl Iterator<Item = Result<Statement, ParseErrors>> + '_ {
+    Cursor::new(sql)
+        .split_raw_statements()
+        .flat_map(|raw_statement| {
+            // the parser borrows the raw statement, its results can't outlive it
+            Parser::new(Lexer::new(raw_statement.iter()))
+                .map(|result| {
+                    result
+                        .map_err(|errors| ParseErrors::for_statement(raw_statement.clone(), errors))
+                })
+                .collect::<Vec<_>>()
+        })
+}
+
+/// The tokens of the source, whitespace and comments are skipped
+pub fn tokenize(sql: &str) -> Tokens {
+    let atoms: Vec<Atom> = RawStatement::new(sql).iter().collect();
+    Tokens(Lexer::new(atoms.into_iter()))
+}
+
+/// Iterator over the tokens of a source, see [`tokenize`]
+pub struct Tokens(Lexer<vec::IntoIter<Atom>>);
+
+impl Iterator for Tokens {
+    type Item = Result<Token, DBError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
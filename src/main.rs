@@ -1,45 +1,73 @@
-pub(crate) mod common;
-pub(crate) mod error;
-pub(crate) mod lexer;
-pub(crate) mod parser;
-pub(crate) mod source;
+use std::{
+    fs,
+    io::{self, Write},
+    process,
+};
 
-use std::io::{self, Cursor, Write};
-
-use crate::source::SplitRawStatements;
-
-use {lexer::Lexer, parser::Parser};
+use dbrs::StatementScanner;
 
 fn main() {
+    // with a path, the statements of that file are parsed instead of starting the REPL
+    match std::env::args().nth(1) {
+        Some(path) => {
+            let source = fs::read_to_string(&path).unwrap_or_else(|err| {
+                eprintln!("Unable to open {}: {}", path, err);
+                process::exit(1);
+            });
+            if !run(&source, Some(&path)) {
+                process::exit(1);
+            }
+        }
+        None => repl(),
+    }
+}
+
+fn repl() {
     let stdin = io::stdin();
     let mut stdout = io::stdout();
     loop {
         let mut command = String::new();
+        let mut scanner = StatementScanner::default();
 
         print!("> ");
         stdout.flush().expect("Unable to flush to stdout");
 
-        while !command.ends_with(";\n") {
+        // read until every statement typed so far is terminated
+        loop {
+            let start = command.len();
             let read = stdin
                 .read_line(&mut command)
                 .expect("Unable to read command");
             if read == 0 {
                 return;
             }
+            command.as_bytes()[start..].iter().for_each(|&byte| {
+                scanner.step(byte);
+            });
+            if scanner.is_complete() {
+                break;
+            }
         }
 
-        for raw_statement in Cursor::new(command).split_raw_statements() {
-            let lexer = Lexer::new(raw_statement.iter());
-            let parser = Parser::new(lexer);
+        run(&command, None);
+    }
+}
 
-            parser.for_each(|st| match st {
-                Err(err) => {
-                    err.print(&raw_statement);
+/// Parses every statement of the source and prints each one or its errors.
+/// Returns whether all statements parsed.
+fn run(source: &str, file_name: Option<&str>) -> bool {
+    let mut parsed = true;
+    for result in dbrs::parse_statements(source) {
+        match result {
+            Ok(statement) => println!("{:?}", statement),
+            Err(errors) => {
+                match file_name {
+                    Some(file_name) => errors.with_file_name(file_name).print(),
+                    None => errors.print(),
                 }
-                Ok(s) => {
-                    println!("{:?}", s);
-                }
-            });
+                parsed = false;
+            }
         }
     }
+    parsed
 }
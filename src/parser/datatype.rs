@@ -1,37 +1,262 @@
 use std::fmt::Display;
 
-use crate::lexer::keyword::Keyword;
+use crate::{
+    common::position::Span,
+    error::DBError,
+    lexer::{keyword::Keyword, literal::Literal, symbol::Symbol, token::TokenKind, Token},
+    Parser,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) enum Datatype {
+pub enum Datatype {
+    SmallInt,
     Integer,
+    BigInt,
     Float,
+    Double,
+    Decimal {
+        precision: Option<u32>,
+        scale: Option<u32>,
+    },
+    Char(Option<u32>),
+    Varchar(Option<u32>),
     String,
     Boolean,
+    Date,
+    Time,
+    Timestamp,
     DateTime,
+    Blob,
+    Uuid,
 }
 
 impl Display for Datatype {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Datatype::SmallInt => write!(f, "SMALLINT"),
             Datatype::Integer => write!(f, "INTEGER"),
+            Datatype::BigInt => write!(f, "BIGINT"),
             Datatype::Float => write!(f, "FLOAT"),
+            Datatype::Double => write!(f, "DOUBLE"),
+            Datatype::Decimal {
+                precision: Some(precision),
+                scale: Some(scale),
+            } => write!(f, "DECIMAL({}, {})", precision, scale),
+            Datatype::Decimal {
+                precision: Some(precision),
+                scale: None,
+            } => write!(f, "DECIMAL({})", precision),
+            Datatype::Decimal { .. } => write!(f, "DECIMAL"),
+            Datatype::Char(Some(length)) => write!(f, "CHAR({})", length),
+            Datatype::Char(None) => write!(f, "CHAR"),
+            Datatype::Varchar(Some(length)) => write!(f, "VARCHAR({})", length),
+            Datatype::Varchar(None) => write!(f, "VARCHAR"),
             Datatype::String => write!(f, "STRING"),
             Datatype::Boolean => write!(f, "BOOLEAN"),
+            Datatype::Date => write!(f, "DATE"),
+            Datatype::Time => write!(f, "TIME"),
+            Datatype::Timestamp => write!(f, "TIMESTAMP"),
             Datatype::DateTime => write!(f, "DATETIME"),
+            Datatype::Blob => write!(f, "BLOB"),
+            Datatype::Uuid => write!(f, "UUID"),
         }
     }
 }
 
 impl Datatype {
+    /// Maps the keywords of the types that take no parameters
     pub(crate) fn from_keyword(keyword: Keyword) -> Option<Datatype> {
         Some(match keyword {
+            Keyword::SmallInt => Datatype::SmallInt,
             Keyword::Int => Datatype::Integer,
+            Keyword::BigInt => Datatype::BigInt,
             Keyword::Float => Datatype::Float,
+            Keyword::Double => Datatype::Double,
             Keyword::String => Datatype::String,
             Keyword::Boolean => Datatype::Boolean,
+            Keyword::Date => Datatype::Date,
+            Keyword::Time => Datatype::Time,
+            Keyword::Timestamp => Datatype::Timestamp,
             Keyword::DateTime => Datatype::DateTime,
+            Keyword::Blob | Keyword::Bytes => Datatype::Blob,
+            Keyword::Uuid => Datatype::Uuid,
             _ => return None, // Only these keywords are valid for types
         })
     }
 }
+
+impl<Tokens> Parser<Tokens>
+where
+    Tokens: Iterator<Item = Result<Token, DBError>>,
+{
+    pub(crate) fn parse_datatype(&mut self) -> Result<Datatype, DBError> {
+        // type names are non-reserved, they arrive as identifiers
+        let token = self.get_next_token()?;
+        let datatype = match token.non_reserved_keyword() {
+            Some(Keyword::Char) => Datatype::Char(self.parse_type_length()?),
+            Some(Keyword::Varchar) => Datatype::Varchar(self.parse_type_length()?),
+            Some(Keyword::Decimal) => self.parse_decimal_parameters()?,
+            keyword => match keyword.and_then(Datatype::from_keyword) {
+                Some(datatype) => datatype,
+                None => return Err(DBError::DatatypeExpected(token)),
+            },
+        };
+
+        Ok(datatype)
+    }
+
+    /// Parses the optional `(length)` of a character type
+    fn parse_type_length(&mut self) -> Result<Option<u32>, DBError> {
+        if self
+            .consume_if(TokenKind::Symbol(Symbol::OpenParanthesis))
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let (length, _) = self.expect_type_parameter(false)?;
+        self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+        Ok(Some(length))
+    }
+
+    /// Parses the optional `(precision [, scale])` of `DECIMAL`
+    fn parse_decimal_parameters(&mut self) -> Result<Datatype, DBError> {
+        if self
+            .consume_if(TokenKind::Symbol(Symbol::OpenParanthesis))
+            .is_none()
+        {
+            return Ok(Datatype::Decimal {
+                precision: None,
+                scale: None,
+            });
+        }
+
+        let (precision, _) = self.expect_type_parameter(false)?;
+
+        let scale = if self.consume_if(TokenKind::Symbol(Symbol::Comma)).is_some() {
+            let (scale, span) = self.expect_type_parameter(true)?;
+            if scale > precision {
+                return Err(DBError::ScaleExceedsPrecision {
+                    scale,
+                    precision,
+                    span,
+                });
+            }
+            Some(scale)
+        } else {
+            None
+        };
+
+        self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+
+        Ok(Datatype::Decimal {
+            precision: Some(precision),
+            scale,
+        })
+    }
+
+    /// Expects an integer literal that fits a type parameter, only scales may be zero
+    fn expect_type_parameter(&mut self, allow_zero: bool) -> Result<(u32, Span), DBError> {
+        let token = self.get_next_token()?;
+        let parameter = match token.kind {
+            TokenKind::Literal(Literal::Integer(value)) => u32::try_from(value)
+                .ok()
+                .filter(|&value| allow_zero || value > 0),
+            _ => None,
+        };
+
+        match parameter {
+            Some(parameter) => Ok((parameter, token.span)),
+            None => Err(DBError::InvalidTypeParameter(token)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Datatype;
+    use crate::{
+        error::DBError,
+        parse,
+        parser::{expression::Expression, statements::Statement},
+    };
+
+    fn column_type(sql: &str) -> Result<Datatype, DBError> {
+        let statement = format!("CREATE TABLE t (a {});", sql);
+        match parse(&statement) {
+            Ok(mut statements) => match statements.remove(0) {
+                Statement::CreateTable { mut columns, .. } => Ok(columns.remove(0).data_type),
+                statement => panic!("not a table definition: {:?}", statement),
+            },
+            Err(errors) => Err(errors.errors()[0].clone()),
+        }
+    }
+
+    #[test]
+    fn parses_types_with_parameters() {
+        assert_eq!(column_type("integer").unwrap(), Datatype::Integer);
+        assert_eq!(
+            column_type("VARCHAR(20)").unwrap(),
+            Datatype::Varchar(Some(20))
+        );
+        assert_eq!(column_type("CHAR").unwrap(), Datatype::Char(None));
+        assert_eq!(
+            column_type("DECIMAL(10, 2)").unwrap(),
+            Datatype::Decimal {
+                precision: Some(10),
+                scale: Some(2)
+            }
+        );
+        assert_eq!(column_type("BYTES").unwrap(), Datatype::Blob);
+    }
+
+    #[test]
+    fn rejects_invalid_parameters() {
+        assert!(matches!(
+            column_type("VARCHAR(0)"),
+            Err(DBError::InvalidTypeParameter(_))
+        ));
+        assert!(matches!(
+            column_type("DECIMAL(2, 3)"),
+            Err(DBError::ScaleExceedsPrecision {
+                scale: 3,
+                precision: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn unknown_type_names_are_not_datatypes() {
+        for sql in ["INTT", "SELECT", "\"int\""] {
+            assert!(
+                matches!(column_type(sql), Err(DBError::DatatypeExpected(_))),
+                "{}",
+                sql
+            );
+        }
+    }
+
+    #[test]
+    fn cast_takes_a_datatype() {
+        let statements = parse("SELECT a::date;").unwrap();
+        let Statement::Query(query) = &statements[0] else {
+            panic!("not a query");
+        };
+        let crate::parser::statements::query::SetExpression::Select(select) = &query.body else {
+            panic!("not a select");
+        };
+        let crate::parser::statements::select::SelectItem::Expression { expression, .. } =
+            &select.projection[0]
+        else {
+            panic!("not an expression");
+        };
+        assert!(matches!(
+            expression,
+            Expression::Cast {
+                data_type: Datatype::Date,
+                ..
+            }
+        ));
+    }
+}
@@ -1,18 +1,600 @@
-use super::operators::binary::BinaryOperator;
-use crate::{common::position::Span, lexer::literal::LiteralType};
+use std::fmt::Display;
+
+use super::comma_separated;
+use super::datatype::Datatype;
+use super::ident::CompoundIdent;
+use super::operators::{binary::BinaryOperator, unary::UnaryOperator};
+use super::statements::query::Query;
+use super::window::WindowType;
+use crate::lexer::literal::Literal;
 
 #[derive(Debug, PartialEq)]
-pub(crate) enum Expression {
-    Literal(LiteralType, Span),
-    Ident(Span),
+pub enum Expression {
+    Literal(Literal),
+    Ident(CompoundIdent),
+    /// `*` as the only argument of a call, as in `COUNT(*)`
+    Wildcard,
     FunctionCall {
-        name: Span,
+        name: CompoundIdent,
         arguments: Vec<Expression>,
+        /// Makes the call a window function
+        over: Option<Box<WindowType>>,
     },
     Binary {
         left: Box<Expression>,
         operator: BinaryOperator,
         right: Box<Expression>,
     },
-    Negation(Box<Expression>),
+    Unary {
+        operator: UnaryOperator,
+        operand: Box<Expression>,
+    },
+    InList {
+        expression: Box<Expression>,
+        list: Vec<Expression>,
+        negated: bool,
+    },
+    /// `expr [NOT] IN (SELECT ...)`
+    InSubquery {
+        expression: Box<Expression>,
+        query: Box<Query>,
+        negated: bool,
+    },
+    Like {
+        expression: Box<Expression>,
+        pattern: Box<Expression>,
+        negated: bool,
+    },
+    /// `expr IS [NOT] NULL`, the only test that is never unknown
+    IsNull {
+        expression: Box<Expression>,
+        negated: bool,
+    },
+    /// `left IS [NOT] DISTINCT FROM right`, compares NULLs as equal to each other
+    IsDistinctFrom {
+        left: Box<Expression>,
+        right: Box<Expression>,
+        negated: bool,
+    },
+    /// `COALESCE(a, b, ...)`, the first argument that is not NULL
+    Coalesce(Vec<Expression>),
+    /// `NULLIF(a, b)`, NULL when both are equal and `a` otherwise
+    NullIf {
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    /// `expr [NOT] BETWEEN low AND high`, both bounds inclusive
+    Between {
+        expression: Box<Expression>,
+        low: Box<Expression>,
+        high: Box<Expression>,
+        negated: bool,
+    },
+    /// `CASE [operand] WHEN ... THEN ... [ELSE ...] END`, the searched form has no operand
+    /// and its conditions are predicates, the simple form compares each one to the operand
+    Case {
+        operand: Option<Box<Expression>>,
+        conditions: Vec<WhenClause>,
+        else_result: Option<Box<Expression>>,
+    },
+    /// `CAST(expr AS type)` or the `expr::type` shorthand
+    Cast {
+        expression: Box<Expression>,
+        data_type: Datatype,
+    },
+    /// `EXTRACT(field FROM expr)`
+    Extract {
+        field: DateTimeField,
+        expression: Box<Expression>,
+    },
+    /// `(SELECT ...)` used as a value, it may refer to columns of the outer query
+    Subquery(Box<Query>),
+    /// `EXISTS (SELECT ...)`, `NOT EXISTS` is its negation
+    Exists(Box<Query>),
+    /// `left <comparison> ANY | ALL (SELECT ...)`
+    Quantified {
+        left: Box<Expression>,
+        operator: BinaryOperator,
+        quantifier: Quantifier,
+        query: Box<Query>,
+    },
+}
+
+impl Expression {
+    /// How loosely the expression binds, on the scale of [`BinaryOperator::precedence`].
+    /// Anything that isn't an operator binds as tight as a single value
+    pub(crate) const fn precedence(&self) -> u8 {
+        match self {
+            Expression::Binary { operator, .. } => operator.precedence(),
+            Expression::Unary { operator, .. } => operator.precedence(),
+            Expression::InList { .. }
+            | Expression::InSubquery { .. }
+            | Expression::Like { .. }
+            | Expression::IsNull { .. }
+            | Expression::IsDistinctFrom { .. }
+            | Expression::Between { .. }
+            | Expression::Quantified { .. } => BinaryOperator::comparison_precedence(),
+            _ => 0,
+        }
+    }
+}
+
+/// Prints an operand, parenthesized when it binds looser than its position allows
+struct Operand<'a>(&'a Expression, u8);
+
+impl Display for Operand<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Operand(expression, max_precedence) = *self;
+        if expression.precedence() > max_precedence {
+            write!(f, "({})", expression)
+        } else {
+            write!(f, "{}", expression)
+        }
+    }
+}
+
+/// Prints the expression as SQL that parses back to the same expression
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let comparison = BinaryOperator::comparison_precedence();
+        let not = |negated: &bool| if *negated { "NOT " } else { "" };
+
+        match self {
+            Expression::Literal(literal) => write!(f, "{}", literal),
+            Expression::Ident(ident) => write!(f, "{}", ident),
+            Expression::Wildcard => write!(f, "*"),
+            Expression::FunctionCall {
+                name,
+                arguments,
+                over,
+            } => {
+                write!(f, "{}({})", name, comma_separated(arguments))?;
+                match over {
+                    Some(window) => write!(f, " OVER {}", window),
+                    None => Ok(()),
+                }
+            }
+            Expression::Binary {
+                left,
+                operator,
+                right,
+            } => {
+                // the side an operator groups towards may hold the same operator unparenthesized
+                let precedence = operator.precedence();
+                let (left_max, right_max) = if operator.is_right_associative() {
+                    (precedence - 1, precedence)
+                } else {
+                    (precedence, precedence - 1)
+                };
+                write!(
+                    f,
+                    "{} {} {}",
+                    Operand(left, left_max),
+                    operator,
+                    Operand(right, right_max)
+                )
+            }
+            Expression::Unary {
+                operator: UnaryOperator::Not,
+                operand,
+            } => write!(
+                f,
+                "NOT {}",
+                Operand(operand, UnaryOperator::Not.precedence())
+            ),
+            Expression::Unary { operator, operand } => match operand.as_ref() {
+                // `- -a` written without the space would start a comment
+                Expression::Unary { .. } => write!(f, "{}({})", operator, operand),
+                operand => write!(f, "{}{}", operator, Operand(operand, 0)),
+            },
+            Expression::InList {
+                expression,
+                list,
+                negated,
+            } => write!(
+                f,
+                "{} {}IN ({})",
+                Operand(expression, comparison),
+                not(negated),
+                comma_separated(list)
+            ),
+            Expression::InSubquery {
+                expression,
+                query,
+                negated,
+            } => write!(
+                f,
+                "{} {}IN ({})",
+                Operand(expression, comparison),
+                not(negated),
+                query
+            ),
+            Expression::Like {
+                expression,
+                pattern,
+                negated,
+            } => write!(
+                f,
+                "{} {}LIKE {}",
+                Operand(expression, comparison),
+                not(negated),
+                Operand(pattern, comparison - 1)
+            ),
+            Expression::IsNull {
+                expression,
+                negated,
+            } => write!(
+                f,
+                "{} IS {}NULL",
+                Operand(expression, comparison),
+                not(negated)
+            ),
+            Expression::IsDistinctFrom {
+                left,
+                right,
+                negated,
+            } => write!(
+                f,
+                "{} IS {}DISTINCT FROM {}",
+                Operand(left, comparison),
+                not(negated),
+                Operand(right, comparison - 1)
+            ),
+            Expression::Coalesce(arguments) => {
+                write!(f, "COALESCE({})", comma_separated(arguments))
+            }
+            Expression::NullIf { left, right } => write!(f, "NULLIF({}, {})", left, right),
+            Expression::Between {
+                expression,
+                low,
+                high,
+                negated,
+            } => write!(
+                f,
+                "{} {}BETWEEN {} AND {}",
+                Operand(expression, comparison),
+                not(negated),
+                Operand(low, comparison - 1),
+                Operand(high, comparison - 1)
+            ),
+            Expression::Case {
+                operand,
+                conditions,
+                else_result,
+            } => {
+                write!(f, "CASE")?;
+                if let Some(operand) = operand {
+                    write!(f, " {}", operand)?;
+                }
+                for WhenClause { condition, result } in conditions {
+                    write!(f, " WHEN {} THEN {}", condition, result)?;
+                }
+                if let Some(else_result) = else_result {
+                    write!(f, " ELSE {}", else_result)?;
+                }
+                write!(f, " END")
+            }
+            Expression::Cast {
+                expression,
+                data_type,
+            } => write!(f, "CAST({} AS {})", expression, data_type),
+            Expression::Extract { field, expression } => {
+                write!(f, "EXTRACT({} FROM {})", field, expression)
+            }
+            Expression::Subquery(query) => write!(f, "({})", query),
+            Expression::Exists(query) => write!(f, "EXISTS ({})", query),
+            Expression::Quantified {
+                left,
+                operator,
+                quantifier,
+                query,
+            } => write!(
+                f,
+                "{} {} {} ({})",
+                Operand(left, comparison),
+                operator,
+                quantifier,
+                query
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantifier {
+    /// `ANY` or its synonym `SOME`
+    Any,
+    All,
+}
+
+impl Display for Quantifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Quantifier::Any => write!(f, "ANY"),
+            Quantifier::All => write!(f, "ALL"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WhenClause {
+    pub condition: Expression,
+    pub result: Expression,
+}
+
+/// The part of a date or time that `EXTRACT` returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateTimeField {
+    Year,
+    Quarter,
+    Month,
+    Week,
+    Day,
+    DayOfWeek,
+    DayOfYear,
+    Hour,
+    Minute,
+    Second,
+    Millisecond,
+    Microsecond,
+    Epoch,
+}
+
+impl DateTimeField {
+    pub(crate) fn get_field(word: &str) -> Option<DateTimeField> {
+        let field = match word.to_lowercase().as_str() {
+            "year" => DateTimeField::Year,
+            "quarter" => DateTimeField::Quarter,
+            "month" => DateTimeField::Month,
+            "week" => DateTimeField::Week,
+            "day" => DateTimeField::Day,
+            "dow" => DateTimeField::DayOfWeek,
+            "doy" => DateTimeField::DayOfYear,
+            "hour" => DateTimeField::Hour,
+            "minute" => DateTimeField::Minute,
+            "second" => DateTimeField::Second,
+            "millisecond" => DateTimeField::Millisecond,
+            "microsecond" => DateTimeField::Microsecond,
+            "epoch" => DateTimeField::Epoch,
+            _ => return None,
+        };
+        Some(field)
+    }
+}
+
+impl Display for DateTimeField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            DateTimeField::Year => "YEAR",
+            DateTimeField::Quarter => "QUARTER",
+            DateTimeField::Month => "MONTH",
+            DateTimeField::Week => "WEEK",
+            DateTimeField::Day => "DAY",
+            DateTimeField::DayOfWeek => "DOW",
+            DateTimeField::DayOfYear => "DOY",
+            DateTimeField::Hour => "HOUR",
+            DateTimeField::Minute => "MINUTE",
+            DateTimeField::Second => "SECOND",
+            DateTimeField::Millisecond => "MILLISECOND",
+            DateTimeField::Microsecond => "MICROSECOND",
+            DateTimeField::Epoch => "EPOCH",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DateTimeField, Expression, Quantifier};
+    use crate::{
+        parse,
+        parser::statements::{query::SetExpression, select::SelectItem, Statement},
+    };
+
+    /// The first item of `SELECT sql`
+    fn expression(sql: &str) -> Expression {
+        let Statement::Query(query) = parse(&format!("SELECT {};", sql)).unwrap().remove(0) else {
+            panic!("not a query");
+        };
+        let SetExpression::Select(mut select) = query.body else {
+            panic!("not a select");
+        };
+        let SelectItem::Expression { expression, .. } = select.projection.remove(0) else {
+            panic!("not an expression");
+        };
+        expression
+    }
+
+    /// The expression with every operation parenthesized, to show how it grouped
+    fn grouping(sql: &str) -> String {
+        group(&expression(sql))
+    }
+
+    fn group(expression: &Expression) -> String {
+        match expression {
+            Expression::Binary {
+                left,
+                operator,
+                right,
+            } => format!("({} {} {})", group(left), operator, group(right)),
+            Expression::Unary { operator, operand } => {
+                format!("({} {})", operator, group(operand))
+            }
+            expression => expression.to_string(),
+        }
+    }
+
+    #[test]
+    fn binary_operators_group_by_precedence() {
+        assert_eq!(grouping("1 + 2 * 3"), "(1 + (2 * 3))");
+        assert_eq!(grouping("1 - 2 - 3"), "((1 - 2) - 3)");
+        assert_eq!(grouping("a | b ^ c & d << 1"), "(a | (b ^ (c & (d << 1))))");
+        assert_eq!(
+            grouping("a OR b AND c = d + 1"),
+            "(a OR (b AND (c = (d + 1))))"
+        );
+        assert_eq!(grouping("a = b < c"), "((a = b) < c)");
+    }
+
+    #[test]
+    fn in_and_like_bind_like_comparisons() {
+        assert_eq!(
+            grouping("a IN (1, 2) AND b LIKE 'x%' OR NOT c"),
+            "((a IN (1, 2) AND b LIKE 'x%') OR (NOT c))"
+        );
+        assert_eq!(grouping("a NOT LIKE b + c"), "a NOT LIKE b + c");
+        assert_eq!(grouping("a + 1 IN (b)"), "a + 1 IN (b)");
+    }
+
+    #[test]
+    fn power_groups_to_the_right() {
+        assert_eq!(grouping("2 ** 3 ** 2"), "(2 ** (3 ** 2))");
+        assert_eq!(grouping("2 * 3 ** 2"), "(2 * (3 ** 2))");
+        assert_eq!(grouping("a ** -b"), "(a ** (- b))");
+    }
+
+    #[test]
+    fn prefix_operators_bind_to_their_operand() {
+        assert_eq!(grouping("-a ** 2"), "((- a) ** 2)");
+        assert_eq!(grouping("NOT a = b"), "(NOT (a = b))");
+        assert_eq!(grouping("NOT a AND b"), "((NOT a) AND b)");
+        assert_eq!(grouping("~a + 1"), "((~ a) + 1)");
+    }
+
+    #[test]
+    fn parses_null_tests_and_functions() {
+        assert!(matches!(
+            expression("a IS NOT NULL"),
+            Expression::IsNull { negated: true, .. }
+        ));
+        assert!(matches!(
+            expression("a IS DISTINCT FROM b"),
+            Expression::IsDistinctFrom { negated: false, .. }
+        ));
+        assert!(matches!(
+            expression("a IS NOT DISTINCT FROM NULL"),
+            Expression::IsDistinctFrom { negated: true, .. }
+        ));
+        assert!(
+            matches!(expression("COALESCE(a, b, NULL)"), Expression::Coalesce(arguments) if arguments.len() == 3)
+        );
+        assert!(matches!(
+            expression("NULLIF(a, 0)"),
+            Expression::NullIf { .. }
+        ));
+        // IS NULL groups left to right with the comparisons
+        assert!(matches!(
+            expression("a = b IS NULL"),
+            Expression::IsNull { expression, .. } if matches!(*expression, Expression::Binary { .. })
+        ));
+        assert!(parse("SELECT NULLIF(a);").is_err());
+        assert!(parse("SELECT a IS 1;").is_err());
+    }
+
+    #[test]
+    fn parses_subqueries() {
+        assert!(matches!(expression("(SELECT 1)"), Expression::Subquery(_)));
+        assert!(matches!(
+            expression("a NOT IN (SELECT b FROM t)"),
+            Expression::InSubquery { negated: true, .. }
+        ));
+        assert!(matches!(
+            expression("a IN (1, (SELECT 2))"),
+            Expression::InList { list, .. } if matches!(list[1], Expression::Subquery(_))
+        ));
+        assert!(matches!(
+            expression("EXISTS (SELECT 1)"),
+            Expression::Exists(_)
+        ));
+        assert!(matches!(
+            expression("NOT EXISTS (SELECT 1)"),
+            Expression::Unary { operand, .. } if matches!(*operand, Expression::Exists(_))
+        ));
+        assert!(matches!(
+            expression("a < ALL (SELECT b FROM t)"),
+            Expression::Quantified {
+                quantifier: Quantifier::All,
+                ..
+            }
+        ));
+        assert!(matches!(
+            expression("a = SOME (SELECT 1)"),
+            Expression::Quantified {
+                quantifier: Quantifier::Any,
+                ..
+            }
+        ));
+        assert!(parse("SELECT a = ANY (1, 2);").is_err());
+        assert!(parse("SELECT EXISTS 1;").is_err());
+    }
+
+    #[test]
+    fn parses_case_expressions() {
+        let Expression::Case {
+            operand,
+            conditions,
+            else_result,
+        } = expression("CASE a WHEN 1 THEN 'one' WHEN 2 THEN 'two' ELSE 'many' END")
+        else {
+            panic!("not a CASE");
+        };
+        assert!(operand.is_some());
+        assert_eq!(conditions.len(), 2);
+        assert!(else_result.is_some());
+
+        let Expression::Case {
+            operand,
+            else_result,
+            ..
+        } = expression("CASE WHEN a > 1 THEN b END")
+        else {
+            panic!("not a CASE");
+        };
+        assert!(operand.is_none() && else_result.is_none());
+        assert!(parse("SELECT CASE a END;").is_err());
+        assert!(parse("SELECT CASE WHEN a THEN b;").is_err());
+    }
+
+    #[test]
+    fn parses_casts_between_and_extract() {
+        assert_eq!(expression("CAST(a AS INT)"), expression("a::INT"),);
+        // casts bind tighter than prefix operators
+        assert!(matches!(
+            expression("-a::INT"),
+            Expression::Unary { operand, .. } if matches!(*operand, Expression::Cast { .. })
+        ));
+        assert!(matches!(
+            expression("a NOT BETWEEN 1 AND b + 2"),
+            Expression::Between { negated: true, high, .. } if matches!(*high, Expression::Binary { .. })
+        ));
+        assert_eq!(
+            grouping("a BETWEEN 1 AND 2 AND b"),
+            "(a BETWEEN 1 AND 2 AND b)"
+        );
+        assert!(matches!(
+            expression("EXTRACT(month FROM d)"),
+            Expression::Extract {
+                field: DateTimeField::Month,
+                ..
+            }
+        ));
+        assert!(parse("SELECT EXTRACT(fortnight FROM d);").is_err());
+        assert!(parse("SELECT CAST(a INT);").is_err());
+    }
+
+    #[test]
+    fn parentheses_are_printed_where_grouping_needs_them() {
+        for sql in [
+            "(1 + 2) * 3",
+            "1 - (2 - 3)",
+            "(2 ** 3) ** 2",
+            "(NOT a) = b",
+            "-(a ** 2)",
+        ] {
+            let printed = parse(&format!("SELECT {};", sql)).unwrap()[0].to_string();
+            assert_eq!(printed, format!("SELECT {};", sql));
+        }
+        let printed = parse("SELECT ((a)) + (b * c);").unwrap()[0].to_string();
+        assert_eq!(printed, "SELECT a + b * c;");
+    }
 }
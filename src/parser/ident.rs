@@ -0,0 +1,153 @@
+use std::fmt::Display;
+
+use crate::{
+    common::position::Span,
+    lexer::{keyword::Keyword, literal::Literal},
+};
+
+/// An identifier as written in the source, with any quoting removed.
+/// Two identifiers are equal when their values are, wherever they were written
+#[derive(Debug, Clone, Eq)]
+pub struct Ident {
+    pub value: String,
+    pub span: Span,
+}
+
+impl Ident {
+    /// An identifier that doesn't come from a source, for building statements in code
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            span: Span::default(),
+        }
+    }
+
+    /// Keywords, literal words and anything the lexer wouldn't read as a single word
+    /// have to be quoted
+    fn needs_quotes(&self) -> bool {
+        let mut chars = self.value.chars();
+        let starts_word = chars
+            .next()
+            .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_');
+        !starts_word
+            || !chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+            || Keyword::get_keyword_kind(&self.value).is_some()
+            || Literal::get_literal(&self.value).is_some()
+    }
+}
+
+impl PartialEq for Ident {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Display for Ident {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.needs_quotes() {
+            write!(f, "\"{}\"", self.value.replace('"', "\"\""))
+        } else {
+            write!(f, "{}", self.value)
+        }
+    }
+}
+
+/// A dot separated name such as `schema.table` or `table.column`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompoundIdent(pub Vec<Ident>);
+
+impl Display for CompoundIdent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self.0.iter().map(|part| part.to_string()).collect();
+        write!(f, "{}", parts.join("."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompoundIdent, Ident};
+    use crate::{
+        parse,
+        parser::{
+            expression::Expression,
+            statements::{query::SetExpression, select::SelectItem, Statement},
+        },
+    };
+
+    fn name(parts: &[&str]) -> CompoundIdent {
+        CompoundIdent(parts.iter().map(|&part| Ident::new(part)).collect())
+    }
+
+    #[test]
+    fn table_names_may_be_qualified() {
+        for sql in [
+            "CREATE TABLE s.t (a INT);",
+            "INSERT INTO s.t VALUES (1);",
+            "UPDATE s.t SET a = 1;",
+            "DELETE FROM s.t;",
+            "DROP TABLE s.t;",
+        ] {
+            let table_name = match parse(sql).unwrap().remove(0) {
+                Statement::CreateTable { table_name, .. }
+                | Statement::Insert { table_name, .. }
+                | Statement::Update { table_name, .. }
+                | Statement::Delete { table_name, .. } => table_name,
+                Statement::DropTable {
+                    mut table_names, ..
+                } => table_names.remove(0),
+                statement => panic!("{:?}", statement),
+            };
+            assert_eq!(table_name, name(&["s", "t"]), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn columns_and_functions_may_be_qualified() {
+        let Statement::Query(query) = parse("SELECT t.a, s.f(t.b) FROM s.t;").unwrap().remove(0)
+        else {
+            panic!("not a query");
+        };
+        let SetExpression::Select(select) = query.body else {
+            panic!("not a single SELECT");
+        };
+        let expressions: Vec<Expression> = select
+            .projection
+            .into_iter()
+            .map(|item| match item {
+                SelectItem::Expression { expression, .. } => expression,
+                item => panic!("{:?}", item),
+            })
+            .collect();
+        assert_eq!(expressions[0], Expression::Ident(name(&["t", "a"])));
+        assert!(matches!(
+            &expressions[1],
+            Expression::FunctionCall { name: function, arguments, .. }
+                if *function == name(&["s", "f"])
+                    && arguments == &[Expression::Ident(name(&["t", "b"]))]
+        ));
+        assert!(parse("SELECT t. FROM t;").is_err());
+    }
+
+    #[test]
+    fn quotes_only_what_would_not_read_back_as_the_same_name() {
+        for (value, printed) in [
+            ("users", "users"),
+            ("_Mixed1", "_Mixed1"),
+            ("select", "\"select\""),
+            ("Key", "\"Key\""),
+            ("null", "\"null\""),
+            ("1a", "\"1a\""),
+            ("a b", "\"a b\""),
+            ("x\"y", "\"x\"\"y\""),
+            ("é", "\"é\""),
+        ] {
+            assert_eq!(Ident::new(value).to_string(), printed);
+        }
+    }
+
+    #[test]
+    fn compound_names_join_with_dots() {
+        let name = CompoundIdent(vec![Ident::new("s"), Ident::new("from")]);
+        assert_eq!(name.to_string(), "s.\"from\"");
+    }
+}
@@ -1,70 +1,80 @@
-pub(crate) mod datatype;
-pub(crate) mod expression;
-pub(crate) mod operators;
-pub(crate) mod statements;
+pub mod datatype;
+pub mod expression;
+pub mod ident;
+pub mod operators;
+pub mod statements;
+pub mod window;
 
-use std::iter::Peekable;
+use std::fmt::Display;
 
-use super::lexer::{keyword::Keyword, symbol::Symbol, token::TokenKind, Token};
+use super::lexer::{keyword::Keyword, literal::Literal, symbol::Symbol, token::TokenKind, Token};
 use crate::{
-    common::{peekable_ext::ConsumeIf, position::Span},
-    error::DBError,
+    common::{
+        peekable_ext::{ConsumeIf, MultiPeekable},
+        position::Span,
+    },
+    error::{DBError, Expected},
 };
-use expression::Expression;
-use operators::binary::BinaryOperator;
-use statements::Statement;
+use expression::{DateTimeField, Expression, Quantifier, WhenClause};
+use ident::{CompoundIdent, Ident};
+use operators::{binary::BinaryOperator, unary::UnaryOperator};
+use statements::{query::Query, Statement};
+
+/// Joins the printed items with `, `
+pub(crate) fn comma_separated<T: Display>(items: &[T]) -> String {
+    let items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+    items.join(", ")
+}
 
 pub(crate) struct Parser<Tokens>
 where
     Tokens: Iterator<Item = Result<Token, DBError>>,
 {
-    tokens: Peekable<Tokens>,
+    tokens: MultiPeekable<Tokens>,
+    /// The token kinds tried since the last consumed token, reported when none of them matches
+    expected: Vec<Expected>,
+    /// Errors of the current statement that the parser recovered from
+    errors: Vec<DBError>,
+    /// How many parentheses are open in the current statement
+    depth: usize,
+    previous: Option<Token>,
 }
 
 impl<Tokens> Iterator for Parser<Tokens>
 where
     Tokens: Iterator<Item = Result<Token, DBError>>,
 {
-    type Item = Result<Statement, DBError>;
+    /// A statement, or every error found in it
+    type Item = Result<Statement, Vec<DBError>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let keyword = match self.expect_keyword_kind() {
-            Ok(keyword) => keyword,
-            Err(DBError::Eof) => return None,
-            Err(err) => return Some(Err(err)),
-        };
-
-        let statement = match keyword {
-            Keyword::Create => self.parse_create_statement(),
-            // Keyword::Alter => self.parse_alter_statement(),
-            Keyword::Drop => self.parse_drop_statement(),
-            Keyword::Insert => self.parse_insert_statement(),
-            Keyword::Select => self.parse_select_statement(),
-            Keyword::Update => self.parse_update_statement(),
-            Keyword::Delete => self.parse_delete_statement(),
-            _ => return Some(Err(DBError::UnexpectedStatement)),
-        };
+        self.tokens.peek()?;
+        self.depth = 0;
 
-        match &statement {
-            Err(DBError::Eof) => None,
+        let statement = self.parse_statement().and_then(|statement| {
+            self.expect(TokenKind::Symbol(Symbol::Semicolon))?;
+            Ok(statement)
+        });
 
-            // traverse the tokens until the next Semicolon
-            Err(_) => {
-                while let Ok(token) = self.get_next_token() {
-                    if token.kind == TokenKind::Symbol(Symbol::Semicolon) {
-                        break;
-                    }
-                }
-                Some(statement)
+        match statement {
+            Ok(statement) if self.errors.is_empty() => Some(Ok(statement)),
+            Ok(_) => Some(Err(std::mem::take(&mut self.errors))),
+            // running out of input is a consequence of the errors already reported
+            Err(DBError::Eof(_) | DBError::UnexpectedEof { .. }) if !self.errors.is_empty() => {
+                Some(Err(std::mem::take(&mut self.errors)))
+            }
+            Err(err) => {
+                let err = match err {
+                    DBError::Eof(span) => DBError::UnexpectedEof {
+                        expected: std::mem::take(&mut self.expected),
+                        span,
+                    },
+                    err => err,
+                };
+                self.errors.push(err);
+                self.skip_statement();
+                Some(Err(std::mem::take(&mut self.errors)))
             }
-
-            Ok(_) => Some(match self.expect(TokenKind::Symbol(Symbol::Semicolon)) {
-                Ok(_) => statement,
-                Err(DBError::Eof) => Err(DBError::UnexpectedEof {
-                    expected: TokenKind::Symbol(Symbol::Semicolon),
-                }),
-                Err(err) => Err(err),
-            }),
         }
     }
 }
@@ -75,49 +85,317 @@ where
 {
     pub(crate) fn new(tokens: Tokens) -> Self {
         Self {
-            tokens: tokens.peekable(),
+            tokens: MultiPeekable::new(tokens),
+            expected: Vec::new(),
+            errors: Vec::new(),
+            depth: 0,
+            previous: None,
+        }
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement, DBError> {
+        if self.peek_is(TokenKind::Symbol(Symbol::OpenParanthesis)) {
+            // a compound query may start with a parenthesized operand
+            let query = self.parse_query()?;
+            return Ok(Statement::Query(Box::new(query)));
+        }
+
+        // USE and TRUNCATE are non-reserved, they only start a statement
+        match self.peek_non_reserved() {
+            Some(Keyword::Use) => {
+                self.get_next_token()?;
+                return self.parse_use_statement();
+            }
+            Some(Keyword::Truncate) => {
+                self.get_next_token()?;
+                return self.parse_truncate_statement();
+            }
+            _ => {}
+        }
+
+        let token = self.get_next_token()?;
+        let TokenKind::Keyword(keyword) = token.kind else {
+            return Err(DBError::UnexpectedStatement(token));
+        };
+
+        match keyword {
+            Keyword::Create => self.parse_create_statement(),
+            Keyword::Alter => self.parse_alter_statement(),
+            Keyword::Drop => self.parse_drop_statement(),
+            Keyword::Insert => self.parse_insert_statement(None),
+            Keyword::Select => self.parse_query_statement(None),
+            Keyword::Update => self.parse_update_statement(None),
+            Keyword::Delete => self.parse_delete_statement(None),
+            Keyword::With => self.parse_with_statement(),
+            _ => Err(DBError::UnexpectedStatement(token)),
+        }
+    }
+
+    /// Skips the rest of a failed statement up to and including its semicolon
+    fn skip_statement(&mut self) {
+        if matches!(&self.previous, Some(token) if token.kind == TokenKind::Symbol(Symbol::Semicolon))
+        {
+            return;
+        }
+        for token in self.tokens.by_ref() {
+            if matches!(
+                token,
+                Ok(Token {
+                    kind: TokenKind::Symbol(Symbol::Semicolon),
+                    ..
+                })
+            ) {
+                break;
+            }
+        }
+    }
+
+    /// Skips the rest of a list item that failed to parse, up to the next `separator`
+    /// or the closing parenthesis of the list, which are left in place.
+    fn skip_list_item(&mut self, depth: usize, separator: Symbol) {
+        // the error may have been raised on the boundary itself
+        if let Some(previous) = &self.previous {
+            let boundary = match previous.kind {
+                TokenKind::Symbol(Symbol::Semicolon) => true,
+                TokenKind::Symbol(Symbol::CloseParanthesis) => self.depth < depth,
+                TokenKind::Symbol(symbol) => symbol == separator && self.depth == depth,
+                _ => false,
+            };
+            if boundary {
+                if previous.kind == TokenKind::Symbol(Symbol::CloseParanthesis) {
+                    self.depth += 1;
+                }
+                self.tokens.push_front(Ok(previous.clone()));
+                self.previous = None;
+                return;
+            }
+        }
+
+        while let Some(Ok(token)) = self.tokens.peek() {
+            let boundary = match token.kind {
+                TokenKind::Symbol(Symbol::Semicolon) => true,
+                TokenKind::Symbol(Symbol::CloseParanthesis) => self.depth <= depth,
+                TokenKind::Symbol(symbol) => symbol == separator && self.depth == depth,
+                _ => false,
+            };
+            if boundary {
+                return;
+            }
+            let _ = self.get_next_token();
         }
     }
 
     fn get_next_token(&mut self) -> Result<Token, DBError> {
-        self.tokens.next().ok_or(DBError::Eof)?
+        let token = self
+            .tokens
+            .next()
+            .ok_or_else(|| DBError::Eof(self.end_span()))??;
+        self.advance(&token);
+        Ok(token)
+    }
+
+    /// Keeps track of the consumed tokens
+    fn advance(&mut self, token: &Token) {
+        self.expected.clear();
+        match token.kind {
+            TokenKind::Symbol(Symbol::OpenParanthesis) => self.depth += 1,
+            TokenKind::Symbol(Symbol::CloseParanthesis) => {
+                self.depth = self.depth.saturating_sub(1)
+            }
+            _ => {}
+        }
+        self.previous = Some(token.clone());
+    }
+
+    fn previous_span(&self) -> Span {
+        self.previous
+            .as_ref()
+            .map(|token| token.span)
+            .unwrap_or_default()
+    }
+
+    /// An empty span right after the last token, where the input ended
+    fn end_span(&self) -> Span {
+        let end = self.previous_span().end + 1;
+        Span { start: end, end }
+    }
+
+    /// Records what would have been valid at the current position
+    fn note_expected(&mut self, expected: impl Into<Expected>) {
+        let expected = expected.into();
+        if !self.expected.contains(&expected) {
+            self.expected.push(expected);
+        }
     }
 
     fn expect(&mut self, expected: TokenKind) -> Result<(), DBError> {
-        match self.get_next_token()? {
-            Token { kind: actual, .. } if expected == actual => Ok(()),
-            token => Err(DBError::Unexpected {
-                found: token,
-                expected,
-            }),
+        if let Some(token) = self.consume_if(expected) {
+            return token.map(|_| ());
+        }
+
+        self.unexpected()
+    }
+
+    /// Fails on the next token, listing everything that was expected in its place
+    fn unexpected<T>(&mut self) -> Result<T, DBError> {
+        let expected = std::mem::take(&mut self.expected);
+        match self.get_next_token() {
+            Ok(found) => Err(DBError::Unexpected { found, expected }),
+            Err(DBError::Eof(span)) => Err(DBError::UnexpectedEof { expected, span }),
+            Err(err) => Err(err),
         }
     }
 
     fn consume_if(&mut self, expected: TokenKind) -> Option<Result<Token, DBError>> {
-        self.tokens
-            .consume_if(|token| matches!(token, Ok(Token { kind, .. }) if *kind == expected))
+        let token = self
+            .tokens
+            .consume_if(|token| matches!(token, Ok(Token { kind, .. }) if *kind == expected));
+        match &token {
+            Some(Ok(token)) => self.advance(token),
+            _ => self.note_expected(expected),
+        }
+        token
     }
 
-    fn expect_keyword_kind(&mut self) -> Result<Keyword, DBError> {
-        match self.get_next_token()? {
-            Token {
+    /// Consumes an operator that may follow an expression. Like the binary operators,
+    /// it is not listed as expected when the expression is followed by something else.
+    fn consume_operator(&mut self, operator: TokenKind) -> bool {
+        let expected = self.expected.len();
+        let consumed = self.consume_if(operator).is_some();
+        self.expected.truncate(expected);
+        consumed
+    }
+
+    /// Checks whether the next token is of the expected kind without consuming it
+    fn peek_is(&mut self, expected: TokenKind) -> bool {
+        let found = self.peek_nth_is(0, expected.clone());
+        if !found {
+            self.note_expected(expected);
+        }
+        found
+    }
+
+    /// Checks whether the token `n` places ahead is of the expected kind
+    fn peek_nth_is(&mut self, n: usize, expected: TokenKind) -> bool {
+        matches!(self.tokens.peek_nth(n), Some(Ok(Token { kind, .. })) if *kind == expected)
+    }
+
+    /// Checks whether the token `n` places ahead is an identifier
+    fn peek_nth_is_identifier(&mut self, n: usize) -> bool {
+        matches!(
+            self.tokens.peek_nth(n),
+            Some(Ok(Token {
+                kind: TokenKind::Ident { .. },
+                ..
+            }))
+        )
+    }
+
+    /// Returns the next keyword without consuming it
+    fn peek_keyword(&mut self) -> Option<Keyword> {
+        match self.tokens.peek() {
+            Some(Ok(Token {
                 kind: TokenKind::Keyword(keyword),
                 ..
-            } => Ok(keyword),
-            token => Err(DBError::KeywordExpected(token)),
+            })) => Some(*keyword),
+            _ => None,
+        }
+    }
+
+    /// Returns the non-reserved keyword the next token spells without consuming it
+    fn peek_non_reserved(&mut self) -> Option<Keyword> {
+        self.peek_nth_non_reserved(0)
+    }
+
+    /// Returns the non-reserved keyword the token `n` places ahead spells
+    fn peek_nth_non_reserved(&mut self, n: usize) -> Option<Keyword> {
+        match self.tokens.peek_nth(n) {
+            Some(Ok(token)) => token.non_reserved_keyword(),
+            _ => None,
+        }
+    }
+
+    /// Consumes a non-reserved keyword, which the lexer hands over as an identifier
+    fn consume_non_reserved(&mut self, keyword: Keyword) -> bool {
+        if self.peek_non_reserved() == Some(keyword) {
+            return self.get_next_token().is_ok();
+        }
+        self.note_expected(TokenKind::Keyword(keyword));
+        false
+    }
+
+    fn expect_non_reserved(&mut self, keyword: Keyword) -> Result<(), DBError> {
+        if self.consume_non_reserved(keyword) {
+            return Ok(());
+        }
+        // fails, listing the keyword with everything else that was expected
+        self.expect(TokenKind::Keyword(keyword))
+    }
+
+    /// Consumes the next token if it is one of `keywords`, which are noted as expected otherwise
+    fn consume_keyword_of(&mut self, keywords: &[Keyword]) -> Option<Keyword> {
+        match self.peek_keyword() {
+            Some(keyword) if keywords.contains(&keyword) => {
+                self.get_next_token().ok()?;
+                Some(keyword)
+            }
+            _ => {
+                for keyword in keywords {
+                    self.note_expected(TokenKind::Keyword(*keyword));
+                }
+                None
+            }
         }
     }
 
-    fn expect_identifier(&mut self) -> Result<Span, DBError> {
+    fn expect_identifier(&mut self) -> Result<Ident, DBError> {
         match self.get_next_token()? {
             Token {
-                kind: TokenKind::Ident,
+                kind: TokenKind::Ident { value, .. },
                 span,
-            } => Ok(span),
+            } => Ok(Ident { value, span }),
             token => Err(DBError::IdentExpected(token)),
         }
     }
 
+    /// Parses `a.b.c`, stopping before a `.*` wildcard
+    fn expect_compound_identifier(&mut self) -> Result<CompoundIdent, DBError> {
+        let first = self.expect_identifier()?;
+        self.parse_compound_identifier_after(first)
+    }
+
+    /// Continues a compound identifier whose first part is already consumed
+    fn parse_compound_identifier_after(&mut self, first: Ident) -> Result<CompoundIdent, DBError> {
+        let mut parts = vec![first];
+        while self.peek_is(TokenKind::Symbol(Symbol::Dot))
+            && !self.peek_nth_is(1, TokenKind::Symbol(Symbol::Star))
+        {
+            self.expect(TokenKind::Symbol(Symbol::Dot))?;
+            parts.push(self.expect_identifier()?);
+        }
+        Ok(CompoundIdent(parts))
+    }
+
+    /// Parses a parenthesized, comma separated list of identifiers: `(a, b, c)`
+    fn parse_identifier_list(&mut self) -> Result<Vec<Ident>, DBError> {
+        self.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
+        let identifiers =
+            self.parse_seperated(Symbol::Comma, |parser| parser.expect_identifier())?;
+        self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+        Ok(identifiers)
+    }
+
+    /// Parses an optional `[AS] alias`
+    fn parse_alias(&mut self) -> Result<Option<Ident>, DBError> {
+        let explicit = self.consume_if(TokenKind::Keyword(Keyword::As)).is_some();
+        // OFFSET and the outer join types are non-reserved, but continue the query
+        let continues = self.at_outer_join() || self.peek_non_reserved() == Some(Keyword::Offset);
+        if explicit || (self.peek_nth_is_identifier(0) && !continues) {
+            return Ok(Some(self.expect_identifier()?));
+        }
+        Ok(None)
+    }
+
     fn parse_predicate(&mut self) -> Result<Option<Expression>, DBError> {
         let predicate = if self
             .consume_if(TokenKind::Keyword(Keyword::Where))
@@ -140,21 +418,62 @@ where
         }
 
         // Handle NOT operator
-        if self.consume_if(TokenKind::Keyword(Keyword::Not)).is_some() {
-            let next_expression = self.parse_expression_of(precedence - 1)?;
-            return Ok(Expression::Negation(Box::new(next_expression)));
+        if precedence == UnaryOperator::Not.precedence() {
+            // a prefix NOT is covered by the expression expected in its place
+            if self.consume_operator(TokenKind::Keyword(Keyword::Not)) {
+                let next_expression = self.parse_expression_of(precedence)?;
+                return Ok(Expression::Unary {
+                    operator: UnaryOperator::Not,
+                    operand: Box::new(next_expression),
+                });
+            }
+            return self.parse_expression_of(precedence - 1);
         }
 
         let mut left = self.parse_expression_of(precedence - 1)?;
 
         loop {
+            if precedence == BinaryOperator::comparison_precedence() {
+                let negated = self.consume_operator(TokenKind::Keyword(Keyword::Not));
+                if negated
+                    || matches!(
+                        self.peek_keyword(),
+                        Some(Keyword::In | Keyword::Like | Keyword::Between)
+                    )
+                {
+                    left = self.parse_membership(left, negated)?;
+                    continue;
+                }
+
+                if self.consume_operator(TokenKind::Keyword(Keyword::Is)) {
+                    left = self.parse_is_predicate(left)?;
+                    continue;
+                }
+            }
+
             let binary_operator = match BinaryOperator::parse_binary_operator(self, precedence) {
                 Some(Ok(operator)) => operator,
                 Some(Err(err)) => return Err(err),
                 None => break,
             };
 
-            let right = self.parse_expression_of(precedence - 1)?;
+            if precedence == BinaryOperator::comparison_precedence() {
+                if let Some(quantifier) = self.parse_quantifier() {
+                    left = Expression::Quantified {
+                        left: Box::new(left),
+                        operator: binary_operator,
+                        quantifier,
+                        query: Box::new(self.parse_parenthesized_query()?),
+                    };
+                    continue;
+                }
+            }
+
+            let right = if binary_operator.is_right_associative() {
+                self.parse_expression_of(precedence)?
+            } else {
+                self.parse_expression_of(precedence - 1)?
+            };
 
             left = Expression::Binary {
                 left: Box::new(left),
@@ -166,32 +485,303 @@ where
         Ok(left)
     }
 
+    /// Parses the right hand side of `[NOT] IN (...)`, `[NOT] LIKE pattern` and
+    /// `[NOT] BETWEEN low AND high`
+    fn parse_membership(
+        &mut self,
+        expression: Expression,
+        negated: bool,
+    ) -> Result<Expression, DBError> {
+        let expression = Box::new(expression);
+        match self.consume_keyword_of(&[Keyword::In, Keyword::Like, Keyword::Between]) {
+            Some(Keyword::In) => {
+                self.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
+                if self.peek_query() {
+                    let query = self.parse_query()?;
+                    self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+                    return Ok(Expression::InSubquery {
+                        expression,
+                        query: Box::new(query),
+                        negated,
+                    });
+                }
+                let list = self.parse_separated_expressions(Symbol::Comma)?;
+                self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+                Ok(Expression::InList {
+                    expression,
+                    list,
+                    negated,
+                })
+            }
+            Some(Keyword::Like) => {
+                let pattern =
+                    self.parse_expression_of(BinaryOperator::comparison_precedence() - 1)?;
+                Ok(Expression::Like {
+                    expression,
+                    pattern: Box::new(pattern),
+                    negated,
+                })
+            }
+            Some(Keyword::Between) => {
+                // the bounds bind tighter than AND, which separates them
+                let low = self.parse_expression_of(BinaryOperator::comparison_precedence() - 1)?;
+                self.expect(TokenKind::Keyword(Keyword::And))?;
+                let high = self.parse_expression_of(BinaryOperator::comparison_precedence() - 1)?;
+                Ok(Expression::Between {
+                    expression,
+                    low: Box::new(low),
+                    high: Box::new(high),
+                    negated,
+                })
+            }
+            _ => self.unexpected(),
+        }
+    }
+
+    /// Consumes the `ANY`, `SOME` or `ALL` that may follow a comparison operator
+    fn parse_quantifier(&mut self) -> Option<Quantifier> {
+        let quantifier = match self.peek_keyword()? {
+            Keyword::Any | Keyword::Some => Quantifier::Any,
+            Keyword::All => Quantifier::All,
+            _ => return None,
+        };
+        self.get_next_token().ok()?;
+        Some(quantifier)
+    }
+
+    /// Parses `(SELECT ...)`
+    fn parse_parenthesized_query(&mut self) -> Result<Query, DBError> {
+        self.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
+        let query = self.parse_query()?;
+        self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+        Ok(query)
+    }
+
+    /// Parses what follows `IS`: `[NOT] NULL` or `[NOT] DISTINCT FROM expr`
+    fn parse_is_predicate(&mut self, expression: Expression) -> Result<Expression, DBError> {
+        let negated = self.consume_if(TokenKind::Keyword(Keyword::Not)).is_some();
+        let expression = Box::new(expression);
+        match self.consume_keyword_of(&[Keyword::Null, Keyword::Distinct]) {
+            Some(Keyword::Null) => Ok(Expression::IsNull {
+                expression,
+                negated,
+            }),
+            Some(Keyword::Distinct) => {
+                self.expect(TokenKind::Keyword(Keyword::From))?;
+                let right =
+                    self.parse_expression_of(BinaryOperator::comparison_precedence() - 1)?;
+                Ok(Expression::IsDistinctFrom {
+                    left: expression,
+                    right: Box::new(right),
+                    negated,
+                })
+            }
+            _ => self.unexpected(),
+        }
+    }
+
     fn parse_factor(&mut self) -> Result<Expression, DBError> {
+        // consuming the token clears the expected set, it is kept for a token that
+        // can't start an expression
+        self.note_expected(Expected::Expression);
+        let expected = self.expected.clone();
         let token = self.get_next_token()?;
+
+        // prefix operators bind tighter than any binary operator
+        if let TokenKind::Symbol(symbol) = token.kind {
+            // the smallest integer is only written negated, its digits alone are out of range
+            if symbol == Symbol::Minus
+                && self.peek_nth_is(0, TokenKind::Literal(Literal::Integer(i64::MIN)))
+            {
+                self.get_next_token()?;
+                return Ok(Expression::Literal(Literal::Integer(i64::MIN)));
+            }
+            if let Some(operator) = UnaryOperator::match_symbol(&symbol) {
+                let operand = self.parse_factor()?;
+                return Ok(Expression::Unary {
+                    operator,
+                    operand: Box::new(operand),
+                });
+            }
+        }
+
+        let mut expression = self.parse_primary(token, expected)?;
+
+        // `::` casts bind tighter than the prefix operators
+        while self.consume_operator(TokenKind::Symbol(Symbol::DoubleColon)) {
+            let data_type = self.parse_datatype()?;
+            expression = Expression::Cast {
+                expression: Box::new(expression),
+                data_type,
+            };
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_primary(
+        &mut self,
+        token: Token,
+        expected: Vec<Expected>,
+    ) -> Result<Expression, DBError> {
         match token.kind {
-            TokenKind::Literal(literal) => Ok(Expression::Literal(literal, token.span)),
-            TokenKind::Ident => {
+            TokenKind::Literal(Literal::Integer(i64::MIN)) => {
+                Err(DBError::IntegerOutOfRange(token.span))
+            }
+            TokenKind::Literal(literal) => Ok(Expression::Literal(literal)),
+            TokenKind::Keyword(Keyword::Case) => self.parse_case(),
+            TokenKind::Keyword(Keyword::Cast) => {
+                self.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
+                let expression = self.parse_expression()?;
+                self.expect(TokenKind::Keyword(Keyword::As))?;
+                let data_type = self.parse_datatype()?;
+                self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+                Ok(Expression::Cast {
+                    expression: Box::new(expression),
+                    data_type,
+                })
+            }
+            TokenKind::Keyword(Keyword::Extract) => {
+                self.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
+                let field = self.expect_date_time_field()?;
+                self.expect(TokenKind::Keyword(Keyword::From))?;
+                let expression = self.parse_expression()?;
+                self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+                Ok(Expression::Extract {
+                    field,
+                    expression: Box::new(expression),
+                })
+            }
+            TokenKind::Keyword(Keyword::Null) => Ok(Expression::Literal(Literal::Null)),
+            TokenKind::Keyword(Keyword::Coalesce) => {
+                self.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
+                let arguments = self.parse_separated_expressions(Symbol::Comma)?;
+                self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+                Ok(Expression::Coalesce(arguments))
+            }
+            TokenKind::Keyword(Keyword::Exists) => Ok(Expression::Exists(Box::new(
+                self.parse_parenthesized_query()?,
+            ))),
+            TokenKind::Keyword(Keyword::NullIf) => {
+                self.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
+                let left = self.parse_expression()?;
+                self.expect(TokenKind::Symbol(Symbol::Comma))?;
+                let right = self.parse_expression()?;
+                self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+                Ok(Expression::NullIf {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                })
+            }
+            TokenKind::Ident { value, .. } => {
+                let ident = self.parse_compound_identifier_after(Ident {
+                    value,
+                    span: token.span,
+                })?;
                 if self
                     .consume_if(TokenKind::Symbol(Symbol::OpenParanthesis))
                     .is_some()
                 {
-                    let expressions = self.parse_separated_expressions(Symbol::Comma)?;
-                    self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+                    let arguments = self.parse_function_arguments()?;
+                    // OVER is non-reserved, without a window after it it's an alias
+                    let over = if self.peek_non_reserved() == Some(Keyword::Over)
+                        && (self.peek_nth_is(1, TokenKind::Symbol(Symbol::OpenParanthesis))
+                            || self.peek_nth_is_identifier(1))
+                    {
+                        self.get_next_token()?;
+                        Some(Box::new(self.parse_window_type()?))
+                    } else {
+                        self.note_expected(TokenKind::Keyword(Keyword::Over));
+                        None
+                    };
                     Ok(Expression::FunctionCall {
-                        name: token.span,
-                        arguments: expressions,
+                        name: ident,
+                        arguments,
+                        over,
                     })
                 } else {
-                    Ok(Expression::Ident(token.span))
+                    Ok(Expression::Ident(ident))
                 }
             }
             TokenKind::Symbol(Symbol::OpenParanthesis) => {
+                if self.peek_query() {
+                    let query = self.parse_query()?;
+                    self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+                    return Ok(Expression::Subquery(Box::new(query)));
+                }
                 let expression = self.parse_expression()?;
                 self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
                 Ok(expression)
             }
-            _ => Err(DBError::UnexpectedToken { found: token }),
+            _ => Err(DBError::Unexpected {
+                found: token,
+                expected,
+            }),
+        }
+    }
+
+    /// Parses a `CASE` expression up to its `END`, `CASE` is already consumed
+    fn parse_case(&mut self) -> Result<Expression, DBError> {
+        let operand = if self.peek_is(TokenKind::Keyword(Keyword::When)) {
+            None
+        } else {
+            Some(Box::new(self.parse_expression()?))
+        };
+
+        let mut conditions = Vec::new();
+        loop {
+            self.expect(TokenKind::Keyword(Keyword::When))?;
+            let condition = self.parse_expression()?;
+            self.expect(TokenKind::Keyword(Keyword::Then))?;
+            let result = self.parse_expression()?;
+            conditions.push(WhenClause { condition, result });
+
+            if !self.peek_is(TokenKind::Keyword(Keyword::When)) {
+                break;
+            }
+        }
+
+        let else_result = if self.consume_if(TokenKind::Keyword(Keyword::Else)).is_some() {
+            Some(Box::new(self.parse_expression()?))
+        } else {
+            None
+        };
+
+        self.expect(TokenKind::Keyword(Keyword::End))?;
+
+        Ok(Expression::Case {
+            operand,
+            conditions,
+            else_result,
+        })
+    }
+
+    fn expect_date_time_field(&mut self) -> Result<DateTimeField, DBError> {
+        let token = self.get_next_token()?;
+        let field = match &token.kind {
+            TokenKind::Ident { value, .. } => DateTimeField::get_field(value),
+            _ => None,
+        };
+        field.ok_or(DBError::DateTimeFieldExpected(token))
+    }
+
+    /// Parses the arguments of a call up to the closing parenthesis, `(` is already consumed
+    fn parse_function_arguments(&mut self) -> Result<Vec<Expression>, DBError> {
+        if self
+            .consume_if(TokenKind::Symbol(Symbol::CloseParanthesis))
+            .is_some()
+        {
+            return Ok(Vec::new());
         }
+
+        let arguments = if self.consume_if(TokenKind::Symbol(Symbol::Star)).is_some() {
+            vec![Expression::Wildcard]
+        } else {
+            self.parse_separated_expressions(Symbol::Comma)?
+        };
+
+        self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+        Ok(arguments)
     }
 
     fn parse_separated_expressions(
@@ -201,6 +791,8 @@ where
         self.parse_seperated(separator, |parser| parser.parse_expression())
     }
 
+    /// Parses a list of items. An item that fails to parse is skipped after recording its
+    /// error, so the rest of the list can still be checked.
     fn parse_seperated<Callback, ReturnType>(
         &mut self,
         separator: Symbol,
@@ -212,8 +804,16 @@ where
         let mut expressions = Vec::new();
 
         loop {
-            let expr = callback(self)?;
-            expressions.push(expr);
+            let depth = self.depth;
+            match callback(self) {
+                Ok(expr) => expressions.push(expr),
+                // nothing left to recover at the end of the input
+                Err(err @ (DBError::Eof(_) | DBError::UnexpectedEof { .. })) => return Err(err),
+                Err(err) => {
+                    self.errors.push(err);
+                    self.skip_list_item(depth, separator);
+                }
+            }
             // Ensure separator before each expression
             if self.consume_if(TokenKind::Symbol(separator)).is_none() {
                 break;
@@ -223,3 +823,237 @@ where
         Ok(expressions)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::{DBError, Expected},
+        lexer::{keyword::Keyword, symbol::Symbol, token::TokenKind, Lexer},
+        parse,
+        source::RawStatement,
+        Parser,
+    };
+
+    fn errors(sql: &str) -> Vec<DBError> {
+        parse(sql)
+            .expect_err("the source should not parse")
+            .errors()
+            .to_vec()
+    }
+
+    fn expected(error: &DBError) -> &[Expected] {
+        match error {
+            DBError::Unexpected { expected, .. } | DBError::UnexpectedEof { expected, .. } => {
+                expected
+            }
+            error => panic!("no expected set in {:?}", error),
+        }
+    }
+
+    #[test]
+    fn lexer_error_after_an_expression_is_reported_once() {
+        let found = errors("SELECT a @ b FROM t;");
+        assert_eq!(found.len(), 1, "{:?}", found);
+        assert!(matches!(found[0], DBError::IllegalCharacter('@', _)));
+
+        let found = errors("SELECT 1 /* abc");
+        assert_eq!(found.len(), 1, "{:?}", found);
+        assert!(matches!(found[0], DBError::UnTerminatedComment(_)));
+    }
+
+    #[test]
+    fn missing_operand_is_reported_after_the_operator() {
+        let found = errors("SELECT a +");
+        assert_eq!(found.len(), 1, "{:?}", found);
+        let DBError::UnexpectedEof { expected, span } = &found[0] else {
+            panic!("expected the end of input, got {:?}", found[0]);
+        };
+        assert_eq!(expected, &[Expected::Expression]);
+        assert_eq!(span.start.absolute.col, 10);
+    }
+
+    #[test]
+    fn missing_expression_is_named() {
+        for sql in [
+            "SELECT FROM t;",
+            "SELECT a, FROM t;",
+            "UPDATE t SET a = ,;",
+            "SELECT a FROM t WHERE;",
+        ] {
+            let found = errors(sql);
+            assert!(
+                expected(&found[0]).contains(&Expected::Expression),
+                "{}: {:?}",
+                sql,
+                found
+            );
+        }
+
+        let found = errors("SELECT");
+        assert_eq!(
+            expected(&found[0]),
+            &[
+                Expected::Token(TokenKind::Keyword(Keyword::Distinct)),
+                Expected::Token(TokenKind::Keyword(Keyword::All)),
+                Expected::Token(TokenKind::Symbol(Symbol::Star)),
+                Expected::Expression,
+            ]
+        );
+    }
+
+    #[test]
+    fn keyword_alternatives_are_listed_as_expected() {
+        let keywords = |keywords: &[Keyword]| -> Vec<Expected> {
+            keywords
+                .iter()
+                .map(|keyword| Expected::Token(TokenKind::Keyword(*keyword)))
+                .collect()
+        };
+
+        let found = errors("ALTER TABLE t ADD a INT;");
+        assert_eq!(
+            expected(&found[0]),
+            keywords(&[
+                Keyword::Column,
+                Keyword::Constraint,
+                Keyword::Primary,
+                Keyword::Unique,
+                Keyword::Check,
+                Keyword::Foreign,
+            ])
+        );
+
+        let found = errors("SELECT * FROM a JOIN b;");
+        assert!(
+            matches!(found[0], DBError::Unexpected { .. }),
+            "{:?}",
+            found
+        );
+        assert!(
+            expected(&found[0]).ends_with(&keywords(&[Keyword::On, Keyword::Using])),
+            "{:?}",
+            found
+        );
+
+        let found = errors("INSERT INTO t");
+        assert!(
+            matches!(found[0], DBError::UnexpectedEof { .. }),
+            "{:?}",
+            found
+        );
+        assert!(expected(&found[0]).ends_with(&keywords(&[Keyword::Values, Keyword::Select])));
+    }
+
+    #[test]
+    fn expected_set_lists_what_may_follow() {
+        let found = errors("SELECT a b c FROM t;");
+        assert_eq!(found.len(), 1, "{:?}", found);
+        let expected = expected(&found[0]);
+        for keyword in [
+            Keyword::From,
+            Keyword::Where,
+            Keyword::Order,
+            Keyword::Limit,
+        ] {
+            assert!(expected.contains(&Expected::Token(TokenKind::Keyword(keyword))));
+        }
+        // operators may follow too, listing them all would drown the rest
+        assert!(!expected.contains(&Expected::Token(TokenKind::Keyword(Keyword::Not))));
+    }
+
+    #[test]
+    fn recovers_within_lists() {
+        let found = errors("SELECT 1 +, 2, 3 * FROM t;");
+        assert_eq!(found.len(), 2, "{:?}", found);
+
+        let found = errors("INSERT INTO t VALUES (1, +), (, 2);");
+        assert_eq!(found.len(), 2, "{:?}", found);
+    }
+
+    #[test]
+    fn recovers_at_the_next_statement() {
+        let found = errors("SELECT FROM t; SELECT 1; DELETE t; SELECT 2;");
+        assert_eq!(found.len(), 2, "{:?}", found);
+
+        let source = RawStatement::new("SELECT FROM t; SELECT 1;");
+        let parsed: Vec<bool> = Parser::new(Lexer::new(source.iter()))
+            .map(|result| result.is_ok())
+            .collect();
+        assert_eq!(parsed, [false, true]);
+    }
+
+    #[test]
+    fn non_reserved_keywords_are_names_elsewhere() {
+        for sql in [
+            "CREATE TABLE t (key INT PRIMARY KEY, FOREIGN KEY (key) REFERENCES u (key));",
+            "SELECT key FROM t AS key WHERE key.key = 1;",
+            "CREATE TABLE e (date DATE, time TIME, timestamp TIMESTAMP, int INT);",
+            "SELECT date, CAST(time AS DATE), stamp::timestamp FROM e;",
+            "SELECT first, last FROM t ORDER BY first NULLS FIRST, last DESC NULLS LAST;",
+            "SELECT left(name, 3), right, full FROM t left JOIN u ON t.a = u.a;",
+            "SELECT * FROM t AS left RIGHT OUTER JOIN u full USING (a);",
+        ] {
+            if let Err(errors) = parse(sql) {
+                panic!("{}\n{}", sql, errors);
+            }
+        }
+        // quoted, the word stays a name where the keyword is expected
+        assert!(parse("CREATE TABLE t (a INT PRIMARY \"key\");").is_err());
+    }
+
+    #[test]
+    fn nulls_order_lists_first_and_last() {
+        let found = errors("SELECT a FROM t ORDER BY a NULLS middle;");
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            expected(&found[0]),
+            [
+                Expected::Token(TokenKind::Keyword(Keyword::First)),
+                Expected::Token(TokenKind::Keyword(Keyword::Last)),
+            ]
+        );
+    }
+
+    #[test]
+    fn join_words_before_join_are_not_aliases() {
+        let statements = parse("SELECT * FROM t left JOIN u ON a, v right;").unwrap();
+        assert_eq!(
+            statements[0].to_string(),
+            "SELECT * FROM t LEFT JOIN u ON a, v AS \"right\";"
+        );
+    }
+
+    #[test]
+    fn smallest_integer_is_written_negated() {
+        let statements = parse("SELECT -9223372036854775808, - 9223372036854775808;").unwrap();
+        assert_eq!(
+            statements[0].to_string(),
+            "SELECT -9223372036854775808, -9223372036854775808;"
+        );
+        for sql in [
+            "SELECT 9223372036854775808;",
+            "SELECT 1 - 9223372036854775808;",
+            "SELECT -(9223372036854775808);",
+        ] {
+            let found = errors(sql);
+            assert!(
+                matches!(found[..], [DBError::IntegerOutOfRange(_)]),
+                "{}: {:?}",
+                sql,
+                found
+            );
+        }
+    }
+
+    #[test]
+    fn numbers_do_not_run_into_aliases() {
+        assert!(matches!(
+            errors("SELECT 1e;")[..],
+            [DBError::MalformedNumber(_)]
+        ));
+        assert_eq!(
+            parse("SELECT 1e3 e3;").unwrap()[0].to_string(),
+            "SELECT 1000.0 AS e3;"
+        );
+    }
+}
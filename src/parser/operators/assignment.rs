@@ -0,0 +1,42 @@
+use std::fmt::Display;
+
+use crate::lexer::symbol::Symbol;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignmentOperator {
+    Assign,
+    AddAssign,
+    SubAssign,
+    MulAssign,
+    DivAssign,
+    ModAssign,
+}
+
+impl AssignmentOperator {
+    pub(crate) const fn match_symbol(symbol: &Symbol) -> Option<Self> {
+        let operator = match symbol {
+            Symbol::Equal => AssignmentOperator::Assign,
+            Symbol::PlusEquals => AssignmentOperator::AddAssign,
+            Symbol::MinusEquals => AssignmentOperator::SubAssign,
+            Symbol::StarEquals => AssignmentOperator::MulAssign,
+            Symbol::DivideEquals => AssignmentOperator::DivAssign,
+            Symbol::PercentEquals => AssignmentOperator::ModAssign,
+            _ => return None,
+        };
+        Some(operator)
+    }
+}
+
+impl Display for AssignmentOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            AssignmentOperator::Assign => Symbol::Equal,
+            AssignmentOperator::AddAssign => Symbol::PlusEquals,
+            AssignmentOperator::SubAssign => Symbol::MinusEquals,
+            AssignmentOperator::MulAssign => Symbol::StarEquals,
+            AssignmentOperator::DivAssign => Symbol::DivideEquals,
+            AssignmentOperator::ModAssign => Symbol::PercentEquals,
+        };
+        write!(f, "{}", symbol)
+    }
+}
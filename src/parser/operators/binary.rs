@@ -7,7 +7,7 @@ use crate::{
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum BinaryOperator {
+pub enum BinaryOperator {
     // binary
     Add,
     Sub,
@@ -28,11 +28,12 @@ pub(crate) enum BinaryOperator {
     And,
     Or,
 
-    // collection
-    In,
-
-    // string
-    Like,
+    // bitwise
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
 }
 
 impl BinaryOperator {
@@ -45,15 +46,21 @@ impl BinaryOperator {
             Symbol::Minus => BinaryOperator::Sub,
             Symbol::Star => BinaryOperator::Mul,
             Symbol::Divide => BinaryOperator::Div,
-            Symbol::Carret => BinaryOperator::Pow,
+            Symbol::DoubleStar => BinaryOperator::Pow,
             Symbol::Percent => BinaryOperator::Mod,
-            // comparision
-            Symbol::Equals => BinaryOperator::Equals,
+            // comparision, a single `=` compares in expression position
+            Symbol::Equal | Symbol::Equals => BinaryOperator::Equals,
             Symbol::NotEquals => BinaryOperator::NotEquals,
             Symbol::LessThan => BinaryOperator::LessThan,
             Symbol::LessThanOrEquals => BinaryOperator::LessThanOrEquals,
             Symbol::GreaterThan => BinaryOperator::GreaterThan,
             Symbol::GreaterThanOrEquals => BinaryOperator::GreaterThanOrEquals,
+            // bitwise
+            Symbol::BitAnd => BinaryOperator::BitAnd,
+            Symbol::BitOr => BinaryOperator::BitOr,
+            Symbol::BitXor => BinaryOperator::BitXor,
+            Symbol::ShiftLeft => BinaryOperator::ShiftLeft,
+            Symbol::ShiftRight => BinaryOperator::ShiftRight,
             _ => return None,
         };
         if precedence == operator.precedence() {
@@ -71,7 +78,8 @@ impl BinaryOperator {
     {
         let token = match parser.tokens.peek()? {
             Ok(token) => token,
-            Err(err) => return Some(Err(err.to_owned())),
+            // consumed, so that recovery doesn't run into the same error again
+            Err(_) => return parser.get_next_token().err().map(Err),
         };
         let operator = match token {
             Token {
@@ -86,48 +94,65 @@ impl BinaryOperator {
                     BinaryOperator::And
                 }
                 Keyword::Or if BinaryOperator::Or.precedence() == precedence => BinaryOperator::Or,
-                Keyword::In if BinaryOperator::In.precedence() == precedence => BinaryOperator::In,
-                Keyword::Like if BinaryOperator::Like.precedence() == precedence => {
-                    BinaryOperator::Like
-                }
                 _ => return None,
             },
 
             _ => return None,
         };
 
-        assert!(parser.tokens.next().is_some());
-        Some(Ok(operator))
+        Some(parser.get_next_token().map(|_| operator))
     }
 
     pub(crate) const fn max_precedence() -> u8 {
-        4
+        BinaryOperator::Or.precedence()
+    }
+
+    /// Precedence shared by the comparison operators, `IN` and `LIKE`
+    pub(crate) const fn comparison_precedence() -> u8 {
+        8
     }
 
+    /// Higher values bind looser, 0 is reserved for factors.
+    /// Level 9 belongs to the prefix `NOT`, see [`UnaryOperator::precedence`]
+    ///
+    /// [`UnaryOperator::precedence`]: super::unary::UnaryOperator::precedence
     pub(crate) const fn precedence(&self) -> u8 {
         match self {
-            BinaryOperator::And => 5,
-            BinaryOperator::Or => 5,
-            BinaryOperator::In => 5,
-            BinaryOperator::Like => 5,
+            BinaryOperator::Or => 11,
+
+            BinaryOperator::And => 10,
+
+            BinaryOperator::Equals => 8,
+            BinaryOperator::NotEquals => 8,
+            BinaryOperator::LessThan => 8,
+            BinaryOperator::LessThanOrEquals => 8,
+            BinaryOperator::GreaterThan => 8,
+            BinaryOperator::GreaterThanOrEquals => 8,
 
-            BinaryOperator::Equals => 4,
-            BinaryOperator::NotEquals => 4,
-            BinaryOperator::LessThan => 4,
-            BinaryOperator::LessThanOrEquals => 4,
-            BinaryOperator::GreaterThan => 4,
-            BinaryOperator::GreaterThanOrEquals => 4,
+            BinaryOperator::BitOr => 7,
 
-            BinaryOperator::Pow => 3,
+            BinaryOperator::BitXor => 6,
+
+            BinaryOperator::BitAnd => 5,
+
+            BinaryOperator::ShiftLeft => 4,
+            BinaryOperator::ShiftRight => 4,
+
+            BinaryOperator::Add => 3,
+            BinaryOperator::Sub => 3,
 
             BinaryOperator::Mul => 2,
             BinaryOperator::Div => 2,
             BinaryOperator::Mod => 2,
 
-            BinaryOperator::Add => 1,
-            BinaryOperator::Sub => 1,
+            BinaryOperator::Pow => 1,
         }
     }
+
+    /// `a ** b ** c` groups as `a ** (b ** c)`, everything else groups left to right
+    pub(crate) const fn is_right_associative(&self) -> bool {
+        matches!(self, BinaryOperator::Pow)
+    }
 }
 
 impl Display for BinaryOperator {
@@ -138,17 +163,20 @@ impl Display for BinaryOperator {
             BinaryOperator::Mul => Symbol::Star,
             BinaryOperator::Div => Symbol::Divide,
             BinaryOperator::Mod => Symbol::Percent,
-            BinaryOperator::Pow => Symbol::Carret,
-            BinaryOperator::Equals => Symbol::Equals,
+            BinaryOperator::Pow => Symbol::DoubleStar,
+            BinaryOperator::Equals => Symbol::Equal,
             BinaryOperator::NotEquals => Symbol::NotEquals,
             BinaryOperator::LessThan => Symbol::LessThan,
             BinaryOperator::LessThanOrEquals => Symbol::LessThanOrEquals,
             BinaryOperator::GreaterThan => Symbol::GreaterThan,
             BinaryOperator::GreaterThanOrEquals => Symbol::GreaterThanOrEquals,
+            BinaryOperator::BitAnd => Symbol::BitAnd,
+            BinaryOperator::BitOr => Symbol::BitOr,
+            BinaryOperator::BitXor => Symbol::BitXor,
+            BinaryOperator::ShiftLeft => Symbol::ShiftLeft,
+            BinaryOperator::ShiftRight => Symbol::ShiftRight,
             BinaryOperator::And => return write!(f, "{}", Keyword::And),
             BinaryOperator::Or => return write!(f, "{}", Keyword::Or),
-            BinaryOperator::In => return write!(f, "{}", Keyword::In),
-            BinaryOperator::Like => return write!(f, "{}", Keyword::Like),
         };
         write!(f, "{}", symbol)
     }
@@ -1 +1,3 @@
-pub(crate) mod binary;
+pub mod assignment;
+pub mod binary;
+pub mod unary;
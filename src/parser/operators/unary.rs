@@ -0,0 +1,49 @@
+use std::fmt::Display;
+
+use crate::lexer::{keyword::Keyword, symbol::Symbol};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOperator {
+    // arithmetic
+    Plus,
+    Minus,
+
+    // bitwise
+    BitNot,
+
+    // logical
+    Not,
+}
+
+impl UnaryOperator {
+    pub(crate) const fn match_symbol(symbol: &Symbol) -> Option<Self> {
+        let operator = match symbol {
+            Symbol::Plus => UnaryOperator::Plus,
+            Symbol::Minus => UnaryOperator::Minus,
+            Symbol::BitNot => UnaryOperator::BitNot,
+            _ => return None,
+        };
+        Some(operator)
+    }
+
+    /// Symbolic operators bind tighter than any binary operator, `NOT` sits
+    /// between AND and the comparisons
+    pub(crate) const fn precedence(&self) -> u8 {
+        match self {
+            UnaryOperator::Not => 9,
+            UnaryOperator::Plus | UnaryOperator::Minus | UnaryOperator::BitNot => 0,
+        }
+    }
+}
+
+impl Display for UnaryOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            UnaryOperator::Plus => Symbol::Plus,
+            UnaryOperator::Minus => Symbol::Minus,
+            UnaryOperator::BitNot => Symbol::BitNot,
+            UnaryOperator::Not => return write!(f, "{}", Keyword::Not),
+        };
+        write!(f, "{}", symbol)
+    }
+}
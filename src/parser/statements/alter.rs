@@ -1,17 +1,36 @@
-use super::{Column, Statement};
+use std::fmt::Display;
+
+use super::{Column, Statement, TableConstraint};
 use crate::{
-    common::position::Span,
-    error::{DBError, IntoParseResult},
+    error::DBError,
     lexer::{keyword::Keyword, symbol::Symbol, token::TokenKind, Token},
+    parser::ident::Ident,
     Parser,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) enum AlterType {
+#[derive(Debug, PartialEq)]
+pub enum AlterType {
     Add(Column),
-    Drop(Span),
+    AddConstraint(TableConstraint),
+    Drop(Ident),
+    DropConstraint(Ident),
     Modify(Column),
-    Rename { old: Span, new: Span },
+    Rename { old: Ident, new: Ident },
+    RenameTable(Ident),
+}
+
+impl Display for AlterType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlterType::Add(column) => write!(f, "ADD COLUMN {}", column),
+            AlterType::AddConstraint(constraint) => write!(f, "ADD {}", constraint),
+            AlterType::Drop(column) => write!(f, "DROP COLUMN {}", column),
+            AlterType::DropConstraint(name) => write!(f, "DROP CONSTRAINT {}", name),
+            AlterType::Modify(column) => write!(f, "MODIFY COLUMN {}", column),
+            AlterType::Rename { old, new } => write!(f, "RENAME COLUMN {} TO {}", old, new),
+            AlterType::RenameTable(name) => write!(f, "RENAME TO {}", name),
+        }
+    }
 }
 
 impl<Tokens> Parser<Tokens>
@@ -21,7 +40,7 @@ where
     pub(crate) fn parse_alter_statement(&mut self) -> Result<Statement, DBError> {
         self.expect(TokenKind::Keyword(Keyword::Table))?;
 
-        let table_name = self.expected_identifier()?;
+        let table_name = self.expect_compound_identifier()?;
 
         let alter_types =
             self.parse_seperated(Symbol::Comma, |parser| parser.parse_alter_type())?;
@@ -33,65 +52,126 @@ where
     }
 
     pub(crate) fn parse_alter_type(&mut self) -> Result<AlterType, DBError> {
-        let Token {
-            kind: TokenKind::Keyword(keyword),
-            ..
-        } = self.get_next_token()?
-        else {
-            return "Alter Type ADD, DROP, MODIFY, or RENAME expected".as_err();
-        };
-
-        let alter_type = match keyword {
-            Keyword::Add => {
-                self.expect(TokenKind::Keyword(Keyword::Column))?;
-                let column = self.parse_create_statement_column()?;
-                AlterType::Add(column)
-            }
-            Keyword::Drop => {
-                self.expect(TokenKind::Keyword(Keyword::Column))?;
-                let column_name_token = self.get_next_token()?;
-                if let TokenKind::Ident = column_name_token.kind {
-                    AlterType::Drop(column_name_token.span)
+        let alter_type = match self.consume_keyword_of(&[
+            Keyword::Add,
+            Keyword::Drop,
+            Keyword::Modify,
+            Keyword::Rename,
+        ]) {
+            Some(Keyword::Add) => {
+                if self
+                    .consume_if(TokenKind::Keyword(Keyword::Column))
+                    .is_some()
+                {
+                    AlterType::Add(self.parse_create_statement_column()?)
                 } else {
-                    return "Table name expected after DROP COLUMN".as_err();
+                    AlterType::AddConstraint(self.parse_table_constraint()?)
                 }
             }
-            Keyword::Modify => {
-                self.expect(TokenKind::Keyword(Keyword::Column))?;
-                let column = self.parse_create_statement_column()?;
-                AlterType::Modify(column)
+            Some(Keyword::Drop) => {
+                if self
+                    .consume_if(TokenKind::Keyword(Keyword::Constraint))
+                    .is_some()
+                {
+                    AlterType::DropConstraint(self.expect_identifier()?)
+                } else {
+                    self.expect(TokenKind::Keyword(Keyword::Column))?;
+                    AlterType::Drop(self.expect_identifier()?)
+                }
             }
-            Keyword::Rename => {
+            Some(Keyword::Modify) => {
                 self.expect(TokenKind::Keyword(Keyword::Column))?;
-                let old_name_token = self.get_next_token()?;
-                let Token {
-                    kind: TokenKind::Ident,
-                    span: old,
-                } = old_name_token
-                else {
-                    return "Old column name expected after RENAME COLUMN".as_err();
-                };
-                self.expect(TokenKind::Keyword(Keyword::To))?;
-
-                let new_name_token = self.get_next_token()?;
-                let Token {
-                    kind: TokenKind::Ident,
-                    span: new,
-                } = new_name_token
-                else {
-                    return "New column name expected after RENAME COLUMN".as_err();
-                };
-                AlterType::Rename { old, new }
+                AlterType::Modify(self.parse_create_statement_column()?)
             }
-
-            keyword => {
-                return Err(DBError::UnExpectedAlterType {
-                    expected: Keyword::Add,
-                    found: TokenKind::Keyword(keyword),
-                })
+            Some(Keyword::Rename) => {
+                if self.consume_if(TokenKind::Keyword(Keyword::To)).is_some() {
+                    AlterType::RenameTable(self.expect_identifier()?)
+                } else {
+                    self.expect(TokenKind::Keyword(Keyword::Column))?;
+                    let old = self.expect_identifier()?;
+                    self.expect(TokenKind::Keyword(Keyword::To))?;
+                    let new = self.expect_identifier()?;
+                    AlterType::Rename { old, new }
+                }
             }
+            _ => return self.unexpected(),
         };
 
         Ok(alter_type)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::AlterType;
+    use crate::{
+        parse,
+        parser::{
+            datatype::Datatype,
+            ident::{CompoundIdent, Ident},
+            statements::{Column, Statement, TableConstraint, TableConstraintKind},
+        },
+    };
+
+    fn alter_types(sql: &str) -> Vec<AlterType> {
+        match parse(sql).unwrap().remove(0) {
+            Statement::AlterTable {
+                table_name,
+                alter_types,
+            } => {
+                assert_eq!(table_name, CompoundIdent(vec![Ident::new("t")]));
+                alter_types
+            }
+            statement => panic!("not an ALTER TABLE: {:?}", statement),
+        }
+    }
+
+    fn column(name: &str, data_type: Datatype) -> Column {
+        Column {
+            name: Ident::new(name),
+            data_type,
+            constraints: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_every_action() {
+        assert_eq!(
+            alter_types(
+                "ALTER TABLE t ADD COLUMN a INT, DROP COLUMN b, MODIFY COLUMN c BIGINT, \
+                 RENAME COLUMN d TO e, RENAME TO u;"
+            ),
+            [
+                AlterType::Add(column("a", Datatype::Integer)),
+                AlterType::Drop(Ident::new("b")),
+                AlterType::Modify(column("c", Datatype::BigInt)),
+                AlterType::Rename {
+                    old: Ident::new("d"),
+                    new: Ident::new("e"),
+                },
+                AlterType::RenameTable(Ident::new("u")),
+            ]
+        );
+    }
+
+    #[test]
+    fn adds_and_drops_constraints() {
+        assert_eq!(
+            alter_types("ALTER TABLE t ADD CONSTRAINT u UNIQUE (a), DROP CONSTRAINT u;"),
+            [
+                AlterType::AddConstraint(TableConstraint {
+                    name: Some(Ident::new("u")),
+                    kind: TableConstraintKind::Unique(vec![Ident::new("a")]),
+                }),
+                AlterType::DropConstraint(Ident::new("u")),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_actions() {
+        assert!(parse("ALTER TABLE t TRUNCATE a;").is_err());
+        assert!(parse("ALTER TABLE t DROP a;").is_err());
+        assert!(parse("ALTER TABLE t;").is_err());
+    }
+}
@@ -1,68 +1,315 @@
-use super::{Column, Statement};
+use super::{
+    Column, ColumnConstraint, ColumnConstraintKind, ForeignKeyReference, Statement,
+    TableConstraint, TableConstraintKind,
+};
 use crate::{
     error::DBError,
     lexer::{keyword::Keyword, symbol::Symbol, token::TokenKind, Token},
-    parser::datatype::Datatype,
+    parser::{expression::Expression, operators::binary::BinaryOperator},
     Parser,
 };
 
+/// An entry between the parentheses of `CREATE TABLE`
+enum TableElement {
+    Column(Column),
+    Constraint(TableConstraint),
+}
+
 impl<Tokens> Parser<Tokens>
 where
     Tokens: Iterator<Item = Result<Token, DBError>>,
 {
     pub(crate) fn parse_create_statement(&mut self) -> Result<Statement, DBError> {
-        self.expect(TokenKind::Keyword(Keyword::Create))?;
-
         // expect either "DATABASE" or "TABLE"
-        let keyword = self.expect_keyword_kind()?;
-
-        match keyword {
-            Keyword::Database => {
+        match self.consume_keyword_of(&[Keyword::Database, Keyword::Table]) {
+            Some(Keyword::Database) => {
+                let if_not_exists = self.parse_if_not_exists()?;
                 let database_name = self.expect_identifier()?;
-                Ok(Statement::CreateDatabase { database_name })
+                Ok(Statement::CreateDatabase {
+                    database_name,
+                    if_not_exists,
+                })
             }
-            Keyword::Table => self.parse_table_definition(),
-            keyword => Err(DBError::UnexpectedKeyword {
-                found: keyword,
-                allowed: vec![Keyword::Database, Keyword::Table],
-            }),
+            Some(Keyword::Table) => self.parse_table_definition(),
+            _ => self.unexpected(),
         }
     }
 
     pub(crate) fn parse_table_definition(&mut self) -> Result<Statement, DBError> {
-        let table_name = self.expect_identifier()?;
+        let if_not_exists = self.parse_if_not_exists()?;
+        let table_name = self.expect_compound_identifier()?;
 
         self.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
 
-        let columns = self.parse_seperated(Symbol::Comma, |parser| {
-            parser.parse_create_statement_column()
-        })?;
+        let elements =
+            self.parse_seperated(Symbol::Comma, |parser| parser.parse_table_element())?;
 
         self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
 
+        let mut columns = Vec::new();
+        let mut constraints = Vec::new();
+        for element in elements {
+            match element {
+                TableElement::Column(column) => columns.push(column),
+                TableElement::Constraint(constraint) => constraints.push(constraint),
+            }
+        }
+
         Ok(Statement::CreateTable {
             table_name,
+            if_not_exists,
             columns,
+            constraints,
         })
     }
 
+    fn parse_table_element(&mut self) -> Result<TableElement, DBError> {
+        match self.peek_keyword() {
+            Some(
+                Keyword::Constraint
+                | Keyword::Primary
+                | Keyword::Unique
+                | Keyword::Check
+                | Keyword::Foreign,
+            ) => Ok(TableElement::Constraint(self.parse_table_constraint()?)),
+            _ => Ok(TableElement::Column(self.parse_create_statement_column()?)),
+        }
+    }
+
     pub(crate) fn parse_create_statement_column(&mut self) -> Result<Column, DBError> {
         let ident = self.expect_identifier()?;
 
-        let data_type_token = self.get_next_token()?;
-        let data_type = match data_type_token.kind {
-            TokenKind::Keyword(keyword) => Datatype::from_keyword(keyword),
-            _ => {
-                return Err(DBError::KeywordExpected(data_type_token));
-            }
-        };
+        let data_type = self.parse_datatype()?;
+
+        let mut constraints = Vec::new();
+        while let Some(
+            Keyword::Constraint
+            | Keyword::Not
+            | Keyword::Null
+            | Keyword::Primary
+            | Keyword::Unique
+            | Keyword::Default
+            | Keyword::Check
+            | Keyword::References,
+        ) = self.peek_keyword()
+        {
+            constraints.push(self.parse_column_constraint()?);
+        }
 
-        let Some(data_type) = data_type else {
-            return Err(DBError::DatatypeExpected(data_type_token));
-        };
         Ok(Column {
             name: ident,
             data_type,
+            constraints,
         })
     }
+
+    /// Parses a column level constraint with an optional `CONSTRAINT name` prefix
+    pub(crate) fn parse_column_constraint(&mut self) -> Result<ColumnConstraint, DBError> {
+        let name = if self
+            .consume_if(TokenKind::Keyword(Keyword::Constraint))
+            .is_some()
+        {
+            Some(self.expect_identifier()?)
+        } else {
+            None
+        };
+
+        let kind = match self.consume_keyword_of(&[
+            Keyword::Not,
+            Keyword::Null,
+            Keyword::Primary,
+            Keyword::Unique,
+            Keyword::Default,
+            Keyword::Check,
+            Keyword::References,
+        ]) {
+            Some(Keyword::Not) => {
+                self.expect(TokenKind::Keyword(Keyword::Null))?;
+                ColumnConstraintKind::NotNull
+            }
+            Some(Keyword::Null) => ColumnConstraintKind::Null,
+            Some(Keyword::Primary) => {
+                self.expect_non_reserved(Keyword::Key)?;
+                ColumnConstraintKind::PrimaryKey
+            }
+            Some(Keyword::Unique) => ColumnConstraintKind::Unique,
+            // comparisons and NOT would be ambiguous with the constraints that follow,
+            // they have to be parenthesized
+            Some(Keyword::Default) => ColumnConstraintKind::Default(
+                self.parse_expression_of(BinaryOperator::comparison_precedence() - 1)?,
+            ),
+            Some(Keyword::Check) => ColumnConstraintKind::Check(self.parse_check_expression()?),
+            Some(Keyword::References) => {
+                ColumnConstraintKind::References(self.parse_foreign_key_reference()?)
+            }
+            _ => return self.unexpected(),
+        };
+
+        Ok(ColumnConstraint { name, kind })
+    }
+
+    /// Parses a table level constraint with an optional `CONSTRAINT name` prefix
+    pub(crate) fn parse_table_constraint(&mut self) -> Result<TableConstraint, DBError> {
+        let name = if self
+            .consume_if(TokenKind::Keyword(Keyword::Constraint))
+            .is_some()
+        {
+            Some(self.expect_identifier()?)
+        } else {
+            None
+        };
+
+        let kind = match self.consume_keyword_of(&[
+            Keyword::Primary,
+            Keyword::Unique,
+            Keyword::Check,
+            Keyword::Foreign,
+        ]) {
+            Some(Keyword::Primary) => {
+                self.expect_non_reserved(Keyword::Key)?;
+                TableConstraintKind::PrimaryKey(self.parse_identifier_list()?)
+            }
+            Some(Keyword::Unique) => TableConstraintKind::Unique(self.parse_identifier_list()?),
+            Some(Keyword::Check) => TableConstraintKind::Check(self.parse_check_expression()?),
+            Some(Keyword::Foreign) => {
+                self.expect_non_reserved(Keyword::Key)?;
+                let columns = self.parse_identifier_list()?;
+                self.expect(TokenKind::Keyword(Keyword::References))?;
+                let references = self.parse_foreign_key_reference()?;
+                TableConstraintKind::ForeignKey {
+                    columns,
+                    references,
+                }
+            }
+            _ => return self.unexpected(),
+        };
+
+        Ok(TableConstraint { name, kind })
+    }
+
+    /// Parses the target of a `REFERENCES` clause: `table [(columns)]`
+    pub(crate) fn parse_foreign_key_reference(&mut self) -> Result<ForeignKeyReference, DBError> {
+        let table_name = self.expect_compound_identifier()?;
+
+        let columns = if self.peek_is(TokenKind::Symbol(Symbol::OpenParanthesis)) {
+            Some(self.parse_identifier_list()?)
+        } else {
+            None
+        };
+
+        Ok(ForeignKeyReference {
+            table_name,
+            columns,
+        })
+    }
+
+    /// Parses the parenthesized condition of a `CHECK` constraint
+    fn parse_check_expression(&mut self) -> Result<Expression, DBError> {
+        self.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
+        let expression = self.parse_expression()?;
+        self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+        Ok(expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parse,
+        parser::{
+            ident::{CompoundIdent, Ident},
+            statements::{
+                ColumnConstraint, ColumnConstraintKind, ForeignKeyReference, Statement,
+                TableConstraint, TableConstraintKind,
+            },
+        },
+    };
+
+    fn table(sql: &str) -> (Vec<Vec<ColumnConstraint>>, Vec<TableConstraint>) {
+        match parse(sql).unwrap().remove(0) {
+            Statement::CreateTable {
+                columns,
+                constraints,
+                ..
+            } => (
+                columns
+                    .into_iter()
+                    .map(|column| column.constraints)
+                    .collect(),
+                constraints,
+            ),
+            statement => panic!("not a CREATE TABLE: {:?}", statement),
+        }
+    }
+
+    fn reference(table: &str, columns: Option<&[&str]>) -> ForeignKeyReference {
+        ForeignKeyReference {
+            table_name: CompoundIdent(vec![Ident::new(table)]),
+            columns: columns.map(|columns| columns.iter().map(|&c| Ident::new(c)).collect()),
+        }
+    }
+
+    #[test]
+    fn parses_column_constraints() {
+        let (columns, _) = table(
+            "CREATE TABLE t (a INT PRIMARY KEY NOT NULL, b INT CONSTRAINT fk REFERENCES u (id) UNIQUE NULL);",
+        );
+        let kinds: Vec<Vec<&ColumnConstraintKind>> = columns
+            .iter()
+            .map(|constraints| constraints.iter().map(|c| &c.kind).collect())
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                vec![
+                    &ColumnConstraintKind::PrimaryKey,
+                    &ColumnConstraintKind::NotNull
+                ],
+                vec![
+                    &ColumnConstraintKind::References(reference("u", Some(&["id"]))),
+                    &ColumnConstraintKind::Unique,
+                    &ColumnConstraintKind::Null,
+                ],
+            ]
+        );
+        assert_eq!(columns[1][0].name, Some(Ident::new("fk")));
+    }
+
+    #[test]
+    fn parses_table_constraints_among_columns() {
+        let (_, constraints) = table(
+            "CREATE TABLE t (a INT, CONSTRAINT pk PRIMARY KEY (a, b), b INT, UNIQUE (b), CHECK (a > b), FOREIGN KEY (b) REFERENCES u);",
+        );
+        let kinds: Vec<&TableConstraintKind> = constraints.iter().map(|c| &c.kind).collect();
+        assert!(matches!(
+            kinds[..],
+            [
+                TableConstraintKind::PrimaryKey(_),
+                TableConstraintKind::Unique(_),
+                TableConstraintKind::Check(_),
+                TableConstraintKind::ForeignKey { .. },
+            ]
+        ));
+        assert_eq!(constraints[0].name, Some(Ident::new("pk")));
+        assert_eq!(
+            kinds[3],
+            &TableConstraintKind::ForeignKey {
+                columns: vec![Ident::new("b")],
+                references: reference("u", None),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_incomplete_constraints() {
+        for sql in [
+            "CREATE TABLE t (a INT PRIMARY);",
+            "CREATE TABLE t (a INT NOT);",
+            "CREATE TABLE t (a INT, PRIMARY KEY ());",
+            "CREATE TABLE t (a INT CHECK a > 1);",
+            "CREATE TABLE t (a INT CONSTRAINT c);",
+        ] {
+            assert!(parse(sql).is_err(), "{}", sql);
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use super::Statement;
+use super::{query::With, Statement};
 use crate::{
     error::DBError,
     lexer::{keyword::Keyword, token::TokenKind, Token},
@@ -9,12 +9,16 @@ impl<Tokens> Parser<Tokens>
 where
     Tokens: Iterator<Item = Result<Token, DBError>>,
 {
-    pub(crate) fn parse_delete_statement(&mut self) -> Result<Statement, DBError> {
+    pub(crate) fn parse_delete_statement(
+        &mut self,
+        with: Option<With>,
+    ) -> Result<Statement, DBError> {
         self.expect(TokenKind::Keyword(Keyword::From))?;
-        let table_name = self.expect_identifier()?;
+        let table_name = self.expect_compound_identifier()?;
         let predicate = self.parse_predicate()?;
 
         Ok(Statement::Delete {
+            with,
             table_name,
             predicate,
         })
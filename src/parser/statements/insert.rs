@@ -1,18 +1,49 @@
-use super::Statement;
+use std::fmt::Display;
+
+use super::{
+    query::{Query, With},
+    Statement,
+};
 use crate::{
     error::DBError,
     lexer::{keyword::Keyword, symbol::Symbol, token::TokenKind, Token},
+    parser::{comma_separated, expression::Expression},
     Parser,
 };
 
+#[derive(Debug, PartialEq)]
+pub enum InsertSource {
+    /// `VALUES (..), (..)`, one entry per row
+    Values(Vec<Vec<Expression>>),
+    Query(Box<Query>),
+}
+
+impl Display for InsertSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InsertSource::Values(rows) => {
+                let rows: Vec<String> = rows
+                    .iter()
+                    .map(|row| format!("({})", comma_separated(row)))
+                    .collect();
+                write!(f, "VALUES {}", rows.join(", "))
+            }
+            InsertSource::Query(query) => write!(f, "{}", query),
+        }
+    }
+}
+
 impl<Tokens> Parser<Tokens>
 where
     Tokens: Iterator<Item = Result<Token, DBError>>,
 {
-    pub(crate) fn parse_insert_statement(&mut self) -> Result<Statement, DBError> {
+    pub(crate) fn parse_insert_statement(
+        &mut self,
+        with: Option<With>,
+    ) -> Result<Statement, DBError> {
         self.expect(TokenKind::Keyword(Keyword::Into))?;
 
-        let table_name = self.expect_identifier()?;
+        let table_name = self.expect_compound_identifier()?;
 
         let mut columns = None;
 
@@ -26,17 +57,92 @@ where
             columns = Some(column_names);
         }
 
-        self.expect(TokenKind::Keyword(Keyword::Values))?;
-        self.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
-
-        let values = self.parse_seperated(Symbol::Comma, |parser| parser.parse_expression())?;
-
-        self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+        let source = match self.consume_keyword_of(&[Keyword::Values, Keyword::Select]) {
+            Some(Keyword::Values) => {
+                let rows = self.parse_seperated(Symbol::Comma, |parser| parser.parse_row())?;
+                InsertSource::Values(rows)
+            }
+            Some(Keyword::Select) => {
+                InsertSource::Query(Box::new(self.parse_query_after_select(None)?))
+            }
+            _ => return self.unexpected(),
+        };
 
         Ok(Statement::Insert {
+            with,
             table_name,
             columns,
-            values,
+            source,
         })
     }
+
+    /// Parses a single parenthesized row of a `VALUES` list
+    pub(crate) fn parse_row(&mut self) -> Result<Vec<Expression>, DBError> {
+        self.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
+        let values = self.parse_separated_expressions(Symbol::Comma)?;
+        self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InsertSource;
+    use crate::{
+        lexer::literal::Literal,
+        parse,
+        parser::{
+            expression::Expression,
+            ident::Ident,
+            statements::{query::SetExpression, Statement},
+        },
+    };
+
+    fn insert(sql: &str) -> (Option<Vec<Ident>>, InsertSource) {
+        match parse(sql).unwrap().remove(0) {
+            Statement::Insert {
+                columns, source, ..
+            } => (columns, source),
+            statement => panic!("not an INSERT: {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn inserts_several_rows() {
+        let (columns, source) = insert("INSERT INTO t (a, b) VALUES (1, 'x'), (2, NULL);");
+        assert_eq!(columns, Some(vec![Ident::new("a"), Ident::new("b")]));
+        let InsertSource::Values(rows) = source else {
+            panic!("not a VALUES list");
+        };
+        assert_eq!(
+            rows,
+            [
+                vec![
+                    Expression::Literal(Literal::Integer(1)),
+                    Expression::Literal(Literal::String("x".to_string())),
+                ],
+                vec![
+                    Expression::Literal(Literal::Integer(2)),
+                    Expression::Literal(Literal::Null),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn inserts_the_rows_of_a_query() {
+        let (columns, source) = insert("INSERT INTO t SELECT a FROM u UNION SELECT 1;");
+        assert_eq!(columns, None);
+        let InsertSource::Query(query) = source else {
+            panic!("not a query");
+        };
+        assert!(matches!(query.body, SetExpression::SetOperation { .. }));
+    }
+
+    #[test]
+    fn needs_values_or_a_query() {
+        assert!(parse("INSERT INTO t (a);").is_err());
+        assert!(parse("INSERT INTO t VALUES;").is_err());
+        assert!(parse("INSERT INTO t VALUES (1), ;").is_err());
+    }
 }
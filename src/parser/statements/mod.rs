@@ -1,63 +1,522 @@
-pub(crate) mod create;
-pub(crate) mod delete;
-pub(crate) mod insert;
-pub(crate) mod select;
-pub(crate) mod update;
-
-use super::{datatype::Datatype, expression::Expression};
-use crate::{common::position::Span, error::DBError, lexer::{Token, keyword::Keyword, token::TokenKind}, parser::Parser};
+pub mod alter;
+pub mod create;
+pub mod delete;
+pub mod insert;
+pub mod query;
+pub mod select;
+pub mod update;
+
+use std::fmt::Display;
+
+use super::{
+    comma_separated, datatype::Datatype, expression::Expression, operators::binary::BinaryOperator,
+};
+use crate::{
+    error::DBError,
+    lexer::{keyword::Keyword, symbol::Symbol, token::TokenKind, Token},
+    parser::ident::{CompoundIdent, Ident},
+    parser::Parser,
+};
+use alter::AlterType;
+use insert::InsertSource;
+use query::{Query, With};
 use update::UpdateSet;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct Column {
-    pub name: Span,
+#[derive(Debug, PartialEq)]
+pub struct Column {
+    pub name: Ident,
     pub data_type: Datatype,
+    pub constraints: Vec<ColumnConstraint>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ColumnConstraint {
+    pub name: Option<Ident>,
+    pub kind: ColumnConstraintKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ColumnConstraintKind {
+    NotNull,
+    Null,
+    PrimaryKey,
+    Unique,
+    Default(Expression),
+    Check(Expression),
+    References(ForeignKeyReference),
 }
 
 #[derive(Debug, PartialEq)]
-pub(crate) enum Statement {
+pub struct TableConstraint {
+    pub name: Option<Ident>,
+    pub kind: TableConstraintKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TableConstraintKind {
+    PrimaryKey(Vec<Ident>),
+    Unique(Vec<Ident>),
+    Check(Expression),
+    ForeignKey {
+        columns: Vec<Ident>,
+        references: ForeignKeyReference,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignKeyReference {
+    pub table_name: CompoundIdent,
+    pub columns: Option<Vec<Ident>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Statement {
     CreateDatabase {
-        database_name: Span,
+        database_name: Ident,
+        if_not_exists: bool,
+    },
+    DropDatabase {
+        database_name: Ident,
+        if_exists: bool,
+    },
+    Use {
+        database_name: Ident,
     },
     // DDL
     CreateTable {
-        table_name: Span,
+        table_name: CompoundIdent,
+        if_not_exists: bool,
         columns: Vec<Column>,
+        constraints: Vec<TableConstraint>,
+    },
+    AlterTable {
+        table_name: CompoundIdent,
+        alter_types: Vec<AlterType>,
     },
     DropTable {
-        table_name: Span,
+        table_names: Vec<CompoundIdent>,
+        if_exists: bool,
+        /// Drops the objects that depend on the tables as well
+        cascade: bool,
+    },
+    Truncate {
+        table_name: CompoundIdent,
     },
     // DML
     Insert {
-        table_name: Span,
-        columns: Option<Vec<Span>>,
-        values: Vec<Expression>,
+        with: Option<With>,
+        table_name: CompoundIdent,
+        columns: Option<Vec<Ident>>,
+        source: InsertSource,
     },
     Update {
-        table_name: Span,
+        with: Option<With>,
+        table_name: CompoundIdent,
         set: Vec<UpdateSet>,
         predicate: Option<Expression>,
     },
     Delete {
-        table_name: Span,
+        with: Option<With>,
+        table_name: CompoundIdent,
         predicate: Option<Expression>,
     },
     // DQL
-    Select {
-        select_expressions: Vec<Expression>,
-        from: Option<Span>,
-        predicate: Option<Expression>,
-    },
+    Query(Box<Query>),
+}
+
+impl Display for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.name, self.data_type)?;
+        for constraint in &self.constraints {
+            write!(f, " {}", constraint)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ColumnConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "CONSTRAINT {} ", name)?;
+        }
+        match &self.kind {
+            ColumnConstraintKind::NotNull => write!(f, "NOT NULL"),
+            ColumnConstraintKind::Null => write!(f, "NULL"),
+            ColumnConstraintKind::PrimaryKey => write!(f, "PRIMARY KEY"),
+            ColumnConstraintKind::Unique => write!(f, "UNIQUE"),
+            // comparisons and NOT are read as the constraints that may follow
+            ColumnConstraintKind::Default(value)
+                if value.precedence() >= BinaryOperator::comparison_precedence() =>
+            {
+                write!(f, "DEFAULT ({})", value)
+            }
+            ColumnConstraintKind::Default(value) => write!(f, "DEFAULT {}", value),
+            ColumnConstraintKind::Check(condition) => write!(f, "CHECK ({})", condition),
+            ColumnConstraintKind::References(reference) => write!(f, "REFERENCES {}", reference),
+        }
+    }
+}
+
+impl Display for TableConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "CONSTRAINT {} ", name)?;
+        }
+        match &self.kind {
+            TableConstraintKind::PrimaryKey(columns) => {
+                write!(f, "PRIMARY KEY ({})", comma_separated(columns))
+            }
+            TableConstraintKind::Unique(columns) => {
+                write!(f, "UNIQUE ({})", comma_separated(columns))
+            }
+            TableConstraintKind::Check(condition) => write!(f, "CHECK ({})", condition),
+            TableConstraintKind::ForeignKey {
+                columns,
+                references,
+            } => write!(
+                f,
+                "FOREIGN KEY ({}) REFERENCES {}",
+                comma_separated(columns),
+                references
+            ),
+        }
+    }
 }
 
+impl Display for ForeignKeyReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.table_name)?;
+        match &self.columns {
+            Some(columns) => write!(f, " ({})", comma_separated(columns)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Prints the statement as canonical SQL, terminated by `;`.
+/// Parsing the output gives back an equal statement
+impl Display for Statement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Statement::CreateDatabase {
+                database_name,
+                if_not_exists,
+            } => {
+                let if_not_exists = if *if_not_exists { "IF NOT EXISTS " } else { "" };
+                write!(f, "CREATE DATABASE {}{}", if_not_exists, database_name)?;
+            }
+            Statement::DropDatabase {
+                database_name,
+                if_exists,
+            } => {
+                let if_exists = if *if_exists { "IF EXISTS " } else { "" };
+                write!(f, "DROP DATABASE {}{}", if_exists, database_name)?;
+            }
+            Statement::Use { database_name } => write!(f, "USE {}", database_name)?,
+            Statement::CreateTable {
+                table_name,
+                if_not_exists,
+                columns,
+                constraints,
+            } => {
+                let if_not_exists = if *if_not_exists { "IF NOT EXISTS " } else { "" };
+                let mut elements: Vec<String> =
+                    columns.iter().map(|column| column.to_string()).collect();
+                elements.extend(constraints.iter().map(|constraint| constraint.to_string()));
+                write!(
+                    f,
+                    "CREATE TABLE {}{} ({})",
+                    if_not_exists,
+                    table_name,
+                    elements.join(", ")
+                )?;
+            }
+            Statement::AlterTable {
+                table_name,
+                alter_types,
+            } => write!(
+                f,
+                "ALTER TABLE {} {}",
+                table_name,
+                comma_separated(alter_types)
+            )?,
+            Statement::DropTable {
+                table_names,
+                if_exists,
+                cascade,
+            } => {
+                let if_exists = if *if_exists { "IF EXISTS " } else { "" };
+                let cascade = if *cascade { " CASCADE" } else { "" };
+                write!(
+                    f,
+                    "DROP TABLE {}{}{}",
+                    if_exists,
+                    comma_separated(table_names),
+                    cascade
+                )?;
+            }
+            Statement::Truncate { table_name } => write!(f, "TRUNCATE TABLE {}", table_name)?,
+            Statement::Insert {
+                with,
+                table_name,
+                columns,
+                source,
+            } => {
+                write!(f, "{}INSERT INTO {}", LeadingWith(with), table_name)?;
+                if let Some(columns) = columns {
+                    write!(f, " ({})", comma_separated(columns))?;
+                }
+                write!(f, " {}", source)?;
+            }
+            Statement::Update {
+                with,
+                table_name,
+                set,
+                predicate,
+            } => {
+                write!(
+                    f,
+                    "{}UPDATE {} SET {}",
+                    LeadingWith(with),
+                    table_name,
+                    comma_separated(set)
+                )?;
+                if let Some(predicate) = predicate {
+                    write!(f, " WHERE {}", predicate)?;
+                }
+            }
+            Statement::Delete {
+                with,
+                table_name,
+                predicate,
+            } => {
+                write!(f, "{}DELETE FROM {}", LeadingWith(with), table_name)?;
+                if let Some(predicate) = predicate {
+                    write!(f, " WHERE {}", predicate)?;
+                }
+            }
+            Statement::Query(query) => write!(f, "{}", query)?,
+        }
+        write!(f, ";")
+    }
+}
+
+/// Prints the `WITH` clause that leads a statement, followed by a space
+struct LeadingWith<'a>(&'a Option<With>);
+
+impl Display for LeadingWith<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(with) => write!(f, "{} ", with),
+            None => Ok(()),
+        }
+    }
+}
 
 impl<Tokens> Parser<Tokens>
 where
     Tokens: Iterator<Item = Result<Token, DBError>>,
 {
     pub(crate) fn parse_drop_statement(&mut self) -> Result<Statement, DBError> {
-        self.expect(TokenKind::Keyword(Keyword::Table))?;
-        let table_name = self.expect_identifier()?;
-        Ok(Statement::DropTable { table_name })
+        match self.consume_keyword_of(&[Keyword::Database, Keyword::Table]) {
+            Some(Keyword::Database) => {
+                let if_exists = self.parse_if_exists()?;
+                let database_name = self.expect_identifier()?;
+                Ok(Statement::DropDatabase {
+                    database_name,
+                    if_exists,
+                })
+            }
+            Some(Keyword::Table) => {
+                let if_exists = self.parse_if_exists()?;
+                let table_names = self
+                    .parse_seperated(Symbol::Comma, |parser| parser.expect_compound_identifier())?;
+                let cascade = self.consume_non_reserved(Keyword::Cascade);
+                Ok(Statement::DropTable {
+                    table_names,
+                    if_exists,
+                    cascade,
+                })
+            }
+            _ => self.unexpected(),
+        }
+    }
+
+    pub(crate) fn parse_use_statement(&mut self) -> Result<Statement, DBError> {
+        let database_name = self.expect_identifier()?;
+        Ok(Statement::Use { database_name })
+    }
+
+    /// Parses `TRUNCATE [TABLE] name`
+    pub(crate) fn parse_truncate_statement(&mut self) -> Result<Statement, DBError> {
+        self.consume_if(TokenKind::Keyword(Keyword::Table));
+        let table_name = self.expect_compound_identifier()?;
+        Ok(Statement::Truncate { table_name })
+    }
+
+    /// Consumes an optional `IF EXISTS`, IF is non-reserved so `if` alone is a name
+    fn parse_if_exists(&mut self) -> Result<bool, DBError> {
+        if self.peek_non_reserved() != Some(Keyword::If)
+            || !self.peek_nth_is(1, TokenKind::Keyword(Keyword::Exists))
+        {
+            self.note_expected(TokenKind::Keyword(Keyword::If));
+            return Ok(false);
+        }
+        self.get_next_token()?;
+        self.get_next_token()?;
+        Ok(true)
+    }
+
+    /// Consumes an optional `IF NOT EXISTS`
+    fn parse_if_not_exists(&mut self) -> Result<bool, DBError> {
+        if self.peek_non_reserved() != Some(Keyword::If)
+            || !self.peek_nth_is(1, TokenKind::Keyword(Keyword::Not))
+        {
+            self.note_expected(TokenKind::Keyword(Keyword::If));
+            return Ok(false);
+        }
+        self.get_next_token()?;
+        self.get_next_token()?;
+        self.expect(TokenKind::Keyword(Keyword::Exists))?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Statement;
+    use crate::{error::DBError, parse};
+
+    fn statement(sql: &str) -> Statement {
+        let mut statements = parse(sql).unwrap_or_else(|errors| panic!("{}\n{}", sql, errors));
+        statements.remove(0)
+    }
+
+    #[test]
+    fn drop_and_use_databases() {
+        assert!(matches!(
+            statement("DROP DATABASE IF EXISTS shop;"),
+            Statement::DropDatabase { database_name, if_exists: true } if database_name.value == "shop"
+        ));
+        assert!(matches!(
+            statement("DROP DATABASE shop;"),
+            Statement::DropDatabase {
+                if_exists: false,
+                ..
+            }
+        ));
+        assert!(matches!(
+            statement("CREATE DATABASE IF NOT EXISTS shop;"),
+            Statement::CreateDatabase {
+                if_not_exists: true,
+                ..
+            }
+        ));
+        assert!(matches!(
+            statement("use shop;"),
+            Statement::Use { database_name } if database_name.value == "shop"
+        ));
+    }
+
+    #[test]
+    fn truncate_takes_an_optional_table() {
+        for sql in ["TRUNCATE t;", "TRUNCATE TABLE t;"] {
+            assert_eq!(statement(sql).to_string(), "TRUNCATE TABLE t;");
+        }
+    }
+
+    #[test]
+    fn drop_table_words_are_names_elsewhere() {
+        assert!(matches!(
+            statement("DROP TABLE IF EXISTS a, b CASCADE;"),
+            Statement::DropTable { table_names, if_exists: true, cascade: true } if table_names.len() == 2
+        ));
+        assert!(matches!(
+            statement("DROP TABLE if, cascade;"),
+            Statement::DropTable { table_names, if_exists: false, cascade: false } if table_names.len() == 2
+        ));
+        assert_eq!(
+            statement("SELECT if, cascade, use, truncate FROM t;").to_string(),
+            "SELECT \"if\", \"cascade\", \"use\", \"truncate\" FROM t;"
+        );
+        assert_eq!(
+            statement("CREATE TABLE if (use INT);").to_string(),
+            "CREATE TABLE \"if\" (\"use\" INTEGER);"
+        );
+    }
+
+    /// Every statement and expression in the canonical form it prints in
+    const CANONICAL: &[&str] = &[
+        "CREATE DATABASE IF NOT EXISTS shop;",
+        "DROP DATABASE IF EXISTS shop;",
+        "USE shop;",
+        "CREATE TABLE IF NOT EXISTS s.users (id INTEGER PRIMARY KEY, name VARCHAR(20) NOT NULL UNIQUE, bal DECIMAL(10, 2) DEFAULT 0.5, c CHAR, d DECIMAL(5), f DOUBLE, g FLOAT, h BIGINT, i SMALLINT, j STRING, k BOOLEAN DEFAULT TRUE, l DATE, m TIME, n TIMESTAMP, o DATETIME, p BLOB, q UUID, r INTEGER CONSTRAINT fk REFERENCES other (id) CHECK (r > 0 AND r < 10) NULL, t INTEGER DEFAULT -1, CONSTRAINT pk PRIMARY KEY (id, name), UNIQUE (name), CHECK (bal >= 0), FOREIGN KEY (r) REFERENCES other);",
+        "ALTER TABLE users ADD COLUMN age INTEGER NOT NULL, DROP COLUMN name, ADD CONSTRAINT u UNIQUE (age), DROP CONSTRAINT u, MODIFY COLUMN age BIGINT, RENAME COLUMN age TO years, RENAME TO people;",
+        "DROP TABLE IF EXISTS a, b.c CASCADE;",
+        "TRUNCATE TABLE t;",
+        "INSERT INTO t VALUES (1, 'a''b', NULL), (2.5, TRUE, FALSE);",
+        "INSERT INTO t (a, b) SELECT a, b FROM u UNION ALL SELECT 1, 2;",
+        "WITH x AS (SELECT 1) INSERT INTO t SELECT * FROM x;",
+        "UPDATE t SET a = 1, b += 2, c -= 3, d *= 4, e /= 5, f %= 6 WHERE id = 1;",
+        "WITH RECURSIVE r (n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM r WHERE n < 10) UPDATE t SET a = (SELECT max(n) FROM r);",
+        "DELETE FROM t WHERE a IS NULL OR b IS NOT NULL;",
+        "SELECT DISTINCT a, b AS c, t.*, s.t.* FROM t AS x JOIN u ON x.id = u.id LEFT JOIN v USING (id) CROSS JOIN w, z RIGHT JOIN q ON TRUE FULL JOIN r ON 1 = 1;",
+        "SELECT * FROM (SELECT 1 AS one) AS d;",
+        "SELECT a FROM t WHERE a = 1 GROUP BY a, b HAVING count(*) > 1 ORDER BY a DESC NULLS LAST, b ASC, c NULLS FIRST LIMIT 10 OFFSET 5;",
+        "SELECT 1 + 2 * 3, (1 + 2) * 3, 1 - (2 - 3), 1 - 2 - 3, 2 ** 3 ** 2, (2 ** 3) ** 2, -a ** 2, -(a ** 2), -(-a), ~a, +a, a & b | c ^ d, (a | b) & c, a << 1 >> 2, a % b / c;",
+        "SELECT NOT a, NOT NOT a, NOT a AND b, NOT (a AND b), a OR b AND c, (a OR b) AND c, a = b = c, a = (b = c), (NOT a) = b, a + (NOT b);",
+        "SELECT a IN (1, 2), a NOT IN (SELECT b FROM t), a LIKE 'x%', a NOT LIKE b + c, a NOT BETWEEN b + 1 AND c * 2, a BETWEEN (b AND c) AND d, a IS NOT DISTINCT FROM b + 1;",
+        "SELECT a = b IN (TRUE), a IS NULL = b, a + b IS NULL, a < ALL (SELECT b FROM t), a = ANY (SELECT 1);",
+        "SELECT CASE a WHEN 1 THEN 'one' ELSE 'many' END, CASE WHEN a > 1 THEN b END, CAST(CAST(a AS VARCHAR(10)) AS INTEGER), -CAST(a AS INTEGER), EXTRACT(YEAR FROM d), COALESCE(a, b, NULL), NULLIF(a, 0), NOT EXISTS (SELECT 1), (SELECT max(a) FROM t);",
+        "SELECT count(*), sum(a) OVER (), rank() OVER w, row_number() OVER (PARTITION BY a, b ORDER BY c DESC ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW), avg(x) OVER (w ORDER BY y RANGE 5 PRECEDING) FROM t WINDOW w AS (PARTITION BY a), v AS (w ORDER BY b);",
+        "SELECT 1 UNION SELECT 2 INTERSECT SELECT 3 EXCEPT ALL SELECT 4;",
+        "(SELECT 1 UNION SELECT 2) ORDER BY 1;",
+        "SELECT a FROM t UNION (SELECT b FROM u ORDER BY b LIMIT 1) ORDER BY a;",
+        "WITH a AS (SELECT 1), b (x, y) AS (SELECT 1, 2) SELECT * FROM a, b;",
+        "SELECT \"select\", \"from\".\"where\", \"a b\", \"x\"\"y\", \"1a\", \"null\", Mixed, _u;",
+        "SELECT 0.1, 1.0, 100000000000000000000.0, 'it''s', '', 'a;b', a ** -b, 1 - -1;",
+        "SELECT -9223372036854775808, 1 - -9223372036854775808, 0.000001, 1.5;",
+    ];
+
+    #[test]
+    fn prints_canonical_sql_that_parses_back() {
+        for sql in CANONICAL {
+            let statements = parse(sql).unwrap_or_else(|errors| panic!("{}\n{}", sql, errors));
+            let printed = statements[0].to_string();
+            assert_eq!(&printed, sql);
+            assert_eq!(parse(&printed).unwrap(), statements);
+        }
+    }
+
+    #[test]
+    fn prints_shorthands_in_full() {
+        for (sql, printed) in [
+            ("truncate t", "TRUNCATE TABLE t;"),
+            (
+                "SELECT a::int, - -a FROM t x LEFT OUTER JOIN u ON TRUE",
+                "SELECT CAST(a AS INTEGER), -(-a) FROM t AS x LEFT JOIN u ON TRUE;",
+            ),
+            (
+                "select a = some (select 1), extract(epoch from d)",
+                "SELECT a = ANY (SELECT 1), EXTRACT(EPOCH FROM d);",
+            ),
+            ("SELECT E'a\\nb', \"a\"\"\"", "SELECT 'a\nb', \"a\"\"\";"),
+            ("SELECT 1.5e-3, 2E2", "SELECT 0.0015, 200.0;"),
+        ] {
+            let sql = format!("{};", sql);
+            assert_eq!(parse(&sql).unwrap()[0].to_string(), printed);
+        }
+    }
+
+    #[test]
+    fn prints_floats_at_the_edge_of_the_range_in_full() {
+        let statements = parse("SELECT 1e300, 1.7976931348623157e308, 5e-324;").unwrap();
+        let printed = statements[0].to_string();
+        assert!(printed.starts_with(&format!("SELECT 1{}.0, ", "0".repeat(300))));
+        assert_eq!(parse(&printed).unwrap(), statements);
+
+        // infinity has no digits to print, the lexer rejects it
+        let errors = parse("SELECT 1e309;").unwrap_err();
+        assert!(matches!(errors.errors(), [DBError::FloatOutOfRange(_)]));
     }
 }
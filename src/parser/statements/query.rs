@@ -0,0 +1,462 @@
+use std::fmt::Display;
+
+use super::{
+    select::{OrderByExpression, Select, SelectItem},
+    Statement,
+};
+use crate::{
+    error::DBError,
+    lexer::{keyword::Keyword, symbol::Symbol, token::TokenKind, Token},
+    parser::{comma_separated, expression::Expression, ident::Ident},
+    Parser,
+};
+
+/// A query with the clauses that apply to its whole result
+#[derive(Debug, PartialEq)]
+pub struct Query {
+    pub with: Option<With>,
+    pub body: SetExpression,
+    pub order_by: Vec<OrderByExpression>,
+    pub limit: Option<Expression>,
+    pub offset: Option<Expression>,
+}
+
+/// `WITH [RECURSIVE] name [(columns)] AS (query), ...`, only parsed so far, see TODO.md
+#[derive(Debug, PartialEq)]
+pub struct With {
+    /// Lets each query refer to its own name
+    pub recursive: bool,
+    pub tables: Vec<CommonTableExpression>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CommonTableExpression {
+    pub name: Ident,
+    pub columns: Option<Vec<Ident>>,
+    pub query: Box<Query>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SetExpression {
+    Select(Box<Select>),
+    /// A parenthesized query, which may carry its own ORDER BY and LIMIT
+    Query(Box<Query>),
+    SetOperation {
+        left: Box<SetExpression>,
+        operator: SetOperator,
+        /// `ALL` keeps duplicate rows
+        all: bool,
+        right: Box<SetExpression>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOperator {
+    Union,
+    Intersect,
+    Except,
+}
+
+impl Display for SetOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetOperator::Union => write!(f, "UNION"),
+            SetOperator::Intersect => write!(f, "INTERSECT"),
+            SetOperator::Except => write!(f, "EXCEPT"),
+        }
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(with) = &self.with {
+            write!(f, "{} ", with)?;
+        }
+        write!(f, "{}", self.body)?;
+        if !self.order_by.is_empty() {
+            write!(f, " ORDER BY {}", comma_separated(&self.order_by))?;
+        }
+        if let Some(limit) = &self.limit {
+            write!(f, " LIMIT {}", limit)?;
+        }
+        if let Some(offset) = &self.offset {
+            write!(f, " OFFSET {}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for With {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WITH ")?;
+        if self.recursive {
+            write!(f, "RECURSIVE ")?;
+        }
+        write!(f, "{}", comma_separated(&self.tables))
+    }
+}
+
+impl Display for CommonTableExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(columns) = &self.columns {
+            write!(f, " ({})", comma_separated(columns))?;
+        }
+        write!(f, " AS ({})", self.query)
+    }
+}
+
+/// Set operations are printed as they nest, the parser only builds trees whose grouping
+/// follows from the operators, anything else is a parenthesized `Query`
+impl Display for SetExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetExpression::Select(select) => write!(f, "{}", select),
+            SetExpression::Query(query) => write!(f, "({})", query),
+            SetExpression::SetOperation {
+                left,
+                operator,
+                all,
+                right,
+            } => {
+                let all = if *all { " ALL" } else { "" };
+                write!(f, "{} {}{} {}", left, operator, all, right)
+            }
+        }
+    }
+}
+
+impl SetExpression {
+    /// The number of columns produced, unknown when a wildcard depends on the schema
+    pub(crate) fn column_count(&self) -> Option<usize> {
+        match self {
+            SetExpression::Select(select) => {
+                let wildcard = select.projection.iter().any(|item| {
+                    matches!(
+                        item,
+                        SelectItem::Wildcard | SelectItem::QualifiedWildcard(_)
+                    )
+                });
+                (!wildcard).then_some(select.projection.len())
+            }
+            SetExpression::Query(query) => query.body.column_count(),
+            SetExpression::SetOperation { left, right, .. } => {
+                left.column_count().or_else(|| right.column_count())
+            }
+        }
+    }
+}
+
+impl<Tokens> Parser<Tokens>
+where
+    Tokens: Iterator<Item = Result<Token, DBError>>,
+{
+    pub(crate) fn parse_query_statement(
+        &mut self,
+        with: Option<With>,
+    ) -> Result<Statement, DBError> {
+        Ok(Statement::Query(Box::new(
+            self.parse_query_after_select(with)?,
+        )))
+    }
+
+    /// Parses the statement that follows a `WITH` clause, `WITH` is already consumed
+    pub(crate) fn parse_with_statement(&mut self) -> Result<Statement, DBError> {
+        let with = Some(self.parse_with()?);
+        match self.consume_keyword_of(&[
+            Keyword::Select,
+            Keyword::Insert,
+            Keyword::Update,
+            Keyword::Delete,
+        ]) {
+            Some(Keyword::Select) => self.parse_query_statement(with),
+            Some(Keyword::Insert) => self.parse_insert_statement(with),
+            Some(Keyword::Update) => self.parse_update_statement(with),
+            Some(Keyword::Delete) => self.parse_delete_statement(with),
+            _ => self.unexpected(),
+        }
+    }
+
+    /// Parses the common table expressions of a `WITH` clause
+    fn parse_with(&mut self) -> Result<With, DBError> {
+        let recursive = self
+            .consume_if(TokenKind::Keyword(Keyword::Recursive))
+            .is_some();
+
+        let tables = self.parse_seperated(Symbol::Comma, |parser| {
+            let name = parser.expect_identifier()?;
+            let columns = if parser.peek_is(TokenKind::Symbol(Symbol::OpenParanthesis)) {
+                Some(parser.parse_identifier_list()?)
+            } else {
+                None
+            };
+
+            parser.expect(TokenKind::Keyword(Keyword::As))?;
+            parser.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
+            let query = parser.parse_query()?;
+            parser.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+
+            Ok(CommonTableExpression {
+                name,
+                columns,
+                query: Box::new(query),
+            })
+        })?;
+
+        Ok(With { recursive, tables })
+    }
+
+    /// Checks whether a query starts at the next token
+    pub(crate) fn peek_query(&mut self) -> bool {
+        matches!(self.peek_keyword(), Some(Keyword::Select | Keyword::With))
+    }
+
+    /// Parses a query starting at `WITH`, `SELECT` or an opening parenthesis
+    pub(crate) fn parse_query(&mut self) -> Result<Query, DBError> {
+        let with = if self.consume_if(TokenKind::Keyword(Keyword::With)).is_some() {
+            Some(self.parse_with()?)
+        } else {
+            None
+        };
+        let first = self.parse_set_primary()?;
+        self.parse_query_after(with, first)
+    }
+
+    /// Parses a query whose leading `SELECT` is already consumed
+    pub(crate) fn parse_query_after_select(
+        &mut self,
+        with: Option<With>,
+    ) -> Result<Query, DBError> {
+        let first = SetExpression::Select(Box::new(self.parse_select()?));
+        self.parse_query_after(with, first)
+    }
+
+    fn parse_query_after(
+        &mut self,
+        with: Option<With>,
+        first: SetExpression,
+    ) -> Result<Query, DBError> {
+        let body = self.parse_set_operations(first)?;
+
+        let order_by = self.parse_order_by()?;
+
+        let limit = if self
+            .consume_if(TokenKind::Keyword(Keyword::Limit))
+            .is_some()
+        {
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
+        let offset = if self.consume_non_reserved(Keyword::Offset) {
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
+        Ok(Query {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+        })
+    }
+
+    /// `UNION` and `EXCEPT` group left to right, below `INTERSECT`
+    fn parse_set_operations(&mut self, first: SetExpression) -> Result<SetExpression, DBError> {
+        let mut left = self.parse_intersections(first)?;
+
+        loop {
+            let operator = match self.peek_keyword() {
+                Some(Keyword::Union) => SetOperator::Union,
+                Some(Keyword::Except) => SetOperator::Except,
+                _ => {
+                    self.note_expected(TokenKind::Keyword(Keyword::Union));
+                    self.note_expected(TokenKind::Keyword(Keyword::Except));
+                    break;
+                }
+            };
+            let token = self.get_next_token()?;
+            let all = self.consume_if(TokenKind::Keyword(Keyword::All)).is_some();
+
+            let right = self.parse_set_primary()?;
+            let right = self.parse_intersections(right)?;
+            left = Self::combine(left, operator, all, right, token)?;
+        }
+
+        Ok(left)
+    }
+
+    fn parse_intersections(&mut self, first: SetExpression) -> Result<SetExpression, DBError> {
+        let mut left = first;
+
+        while let Some(token) = self.consume_if(TokenKind::Keyword(Keyword::Intersect)) {
+            let token = token?;
+            let all = self.consume_if(TokenKind::Keyword(Keyword::All)).is_some();
+            let right = self.parse_set_primary()?;
+            left = Self::combine(left, SetOperator::Intersect, all, right, token)?;
+        }
+
+        Ok(left)
+    }
+
+    /// Parses a single `SELECT` block or a parenthesized query
+    fn parse_set_primary(&mut self) -> Result<SetExpression, DBError> {
+        if self
+            .consume_if(TokenKind::Symbol(Symbol::OpenParanthesis))
+            .is_some()
+        {
+            let query = self.parse_query()?;
+            self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+            return Ok(SetExpression::Query(Box::new(query)));
+        }
+
+        self.expect(TokenKind::Keyword(Keyword::Select))?;
+        Ok(SetExpression::Select(Box::new(self.parse_select()?)))
+    }
+
+    /// Both sides must produce the same number of columns, checked here when it is known
+    /// without the schema. Wildcards and column types are left to execution, see TODO.md
+    fn combine(
+        left: SetExpression,
+        operator: SetOperator,
+        all: bool,
+        right: SetExpression,
+        token: Token,
+    ) -> Result<SetExpression, DBError> {
+        if let (Some(left), Some(right)) = (left.column_count(), right.column_count()) {
+            if left != right {
+                return Err(DBError::ColumnCountMismatch {
+                    operator,
+                    left,
+                    right,
+                    span: token.span,
+                });
+            }
+        }
+
+        Ok(SetExpression::SetOperation {
+            left: Box::new(left),
+            operator,
+            all,
+            right: Box::new(right),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Query, SetExpression, SetOperator, With};
+    use crate::{
+        error::DBError,
+        parse,
+        parser::{ident::Ident, statements::Statement},
+    };
+
+    fn query(sql: &str) -> Query {
+        match parse(sql).unwrap().remove(0) {
+            Statement::Query(query) => *query,
+            statement => panic!("not a query: {:?}", statement),
+        }
+    }
+
+    /// The set operations with every one parenthesized, `S` stands for a single SELECT
+    fn shape(body: &SetExpression) -> String {
+        match body {
+            SetExpression::Select(_) => "S".to_string(),
+            SetExpression::Query(query) => format!("[{}]", shape(&query.body)),
+            SetExpression::SetOperation {
+                left,
+                operator,
+                all,
+                right,
+            } => format!(
+                "({} {}{} {})",
+                shape(left),
+                operator,
+                if *all { " ALL" } else { "" },
+                shape(right)
+            ),
+        }
+    }
+
+    #[test]
+    fn intersect_binds_tighter() {
+        let query = query("SELECT 1 UNION SELECT 2 INTERSECT SELECT 3 EXCEPT ALL SELECT 4;");
+        assert_eq!(
+            shape(&query.body),
+            "((S UNION (S INTERSECT S)) EXCEPT ALL S)"
+        );
+    }
+
+    #[test]
+    fn order_by_and_limit_apply_to_the_whole_result() {
+        let query =
+            query("SELECT a FROM t UNION (SELECT b FROM u ORDER BY b LIMIT 1) ORDER BY a LIMIT 5;");
+        assert_eq!(shape(&query.body), "(S UNION [S])");
+        assert_eq!(query.order_by.len(), 1);
+        assert!(query.limit.is_some());
+        let SetExpression::SetOperation { right, .. } = &query.body else {
+            panic!("not a set operation");
+        };
+        let SetExpression::Query(inner) = right.as_ref() else {
+            panic!("not parenthesized");
+        };
+        assert!(inner.limit.is_some());
+    }
+
+    #[test]
+    fn column_counts_must_match_when_known() {
+        let errors = parse("SELECT 1, 2 UNION SELECT 1;").unwrap_err();
+        assert!(matches!(
+            errors.errors(),
+            [DBError::ColumnCountMismatch {
+                operator: SetOperator::Union,
+                left: 2,
+                right: 1,
+                ..
+            }]
+        ));
+        // a wildcard depends on the schema, left to execution
+        assert!(parse("SELECT * UNION SELECT 1, 2;").is_ok());
+    }
+
+    fn with(sql: &str) -> With {
+        let with = match parse(sql).unwrap().remove(0) {
+            Statement::Query(query) => query.with,
+            Statement::Insert { with, .. }
+            | Statement::Update { with, .. }
+            | Statement::Delete { with, .. } => with,
+            statement => panic!("takes no WITH: {:?}", statement),
+        };
+        with.unwrap_or_else(|| panic!("no WITH in {}", sql))
+    }
+
+    #[test]
+    fn with_precedes_queries_and_data_changes() {
+        for sql in [
+            "WITH a AS (SELECT 1) SELECT * FROM a;",
+            "WITH a AS (SELECT 1) INSERT INTO t SELECT * FROM a;",
+            "WITH a AS (SELECT 1) UPDATE t SET x = (SELECT * FROM a);",
+            "WITH a AS (SELECT 1) DELETE FROM t;",
+        ] {
+            assert_eq!(with(sql).tables[0].name, Ident::new("a"), "{}", sql);
+        }
+        assert!(parse("WITH a AS (SELECT 1) CREATE TABLE t (a INT);").is_err());
+        assert!(parse("WITH a (SELECT 1) SELECT 1;").is_err());
+    }
+
+    #[test]
+    fn recursive_tables_name_their_columns() {
+        let with = with(
+            "WITH RECURSIVE r (n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM r WHERE n < 10), \
+             s AS (SELECT 2) SELECT * FROM r, s;",
+        );
+        assert!(with.recursive);
+        assert_eq!(with.tables[0].columns, Some(vec![Ident::new("n")]));
+        assert_eq!(with.tables[1].columns, None);
+        assert_eq!(shape(&with.tables[0].query.body), "(S UNION ALL S)");
+    }
+}
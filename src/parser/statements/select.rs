@@ -1,33 +1,620 @@
-use super::Statement;
+use std::fmt::Display;
+
+use super::query::Query;
 use crate::{
     error::DBError,
     lexer::{keyword::Keyword, symbol::Symbol, token::TokenKind, Token},
+    parser::{
+        comma_separated,
+        expression::Expression,
+        ident::{CompoundIdent, Ident},
+        window::NamedWindow,
+    },
     Parser,
 };
 
+#[derive(Debug, PartialEq)]
+pub struct Select {
+    pub distinct: bool,
+    pub projection: Vec<SelectItem>,
+    /// Comma separated tables, empty when there is no FROM clause
+    pub from: Vec<TableReference>,
+    pub predicate: Option<Expression>,
+    pub group_by: Vec<Expression>,
+    pub having: Option<Expression>,
+    pub windows: Vec<NamedWindow>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SelectItem {
+    /// `*`
+    Wildcard,
+    /// `table.*` or `schema.table.*`
+    QualifiedWildcard(CompoundIdent),
+    Expression {
+        expression: Expression,
+        alias: Option<Ident>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TableReference {
+    Table {
+        name: CompoundIdent,
+        alias: Option<Ident>,
+    },
+    /// `(SELECT ...) [AS] alias`
+    Derived {
+        query: Box<Query>,
+        alias: Option<Ident>,
+    },
+    Join {
+        left: Box<TableReference>,
+        right: Box<TableReference>,
+        join_type: JoinType,
+        /// `CROSS JOIN` takes no condition
+        constraint: Option<JoinConstraint>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinType {
+    Inner,
+    Left,
+    Right,
+    Full,
+    Cross,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum JoinConstraint {
+    On(Expression),
+    Using(Vec<Ident>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OrderByExpression {
+    pub expression: Expression,
+    pub direction: Option<SortDirection>,
+    pub nulls: Option<NullsOrder>,
+}
+
+impl Display for Select {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SELECT ")?;
+        if self.distinct {
+            write!(f, "DISTINCT ")?;
+        }
+        write!(f, "{}", comma_separated(&self.projection))?;
+        if !self.from.is_empty() {
+            write!(f, " FROM {}", comma_separated(&self.from))?;
+        }
+        if let Some(predicate) = &self.predicate {
+            write!(f, " WHERE {}", predicate)?;
+        }
+        if !self.group_by.is_empty() {
+            write!(f, " GROUP BY {}", comma_separated(&self.group_by))?;
+        }
+        if let Some(having) = &self.having {
+            write!(f, " HAVING {}", having)?;
+        }
+        if !self.windows.is_empty() {
+            write!(f, " WINDOW {}", comma_separated(&self.windows))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for SelectItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectItem::Wildcard => write!(f, "*"),
+            SelectItem::QualifiedWildcard(table) => write!(f, "{}.*", table),
+            SelectItem::Expression { expression, alias } => {
+                write!(f, "{}{}", expression, Alias(alias))
+            }
+        }
+    }
+}
+
+impl Display for TableReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableReference::Table { name, alias } => write!(f, "{}{}", name, Alias(alias)),
+            TableReference::Derived { query, alias } => write!(f, "({}){}", query, Alias(alias)),
+            TableReference::Join {
+                left,
+                right,
+                join_type,
+                constraint,
+            } => {
+                write!(f, "{} {} {}", left, join_type, right)?;
+                match constraint {
+                    Some(JoinConstraint::On(condition)) => write!(f, " ON {}", condition),
+                    Some(JoinConstraint::Using(columns)) => {
+                        write!(f, " USING ({})", comma_separated(columns))
+                    }
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Prints ` AS alias`, or nothing without an alias
+struct Alias<'a>(&'a Option<Ident>);
+
+impl Display for Alias<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(alias) => write!(f, " AS {}", alias),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for JoinType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JoinType::Inner => write!(f, "JOIN"),
+            JoinType::Left => write!(f, "LEFT JOIN"),
+            JoinType::Right => write!(f, "RIGHT JOIN"),
+            JoinType::Full => write!(f, "FULL JOIN"),
+            JoinType::Cross => write!(f, "CROSS JOIN"),
+        }
+    }
+}
+
+impl Display for OrderByExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expression)?;
+        match self.direction {
+            Some(SortDirection::Asc) => write!(f, " ASC")?,
+            Some(SortDirection::Desc) => write!(f, " DESC")?,
+            None => {}
+        }
+        match self.nulls {
+            Some(NullsOrder::First) => write!(f, " NULLS FIRST"),
+            Some(NullsOrder::Last) => write!(f, " NULLS LAST"),
+            None => Ok(()),
+        }
+    }
+}
+
 impl<Tokens> Parser<Tokens>
 where
     Tokens: Iterator<Item = Result<Token, DBError>>,
 {
-    pub(crate) fn parse_select_statement(&mut self) -> Result<Statement, DBError> {
-        let expressions = self.parse_separated_expressions(Symbol::Comma)?;
-
-        if self.consume_if(TokenKind::Keyword(Keyword::From)).is_none() {
-            return Ok(Statement::Select {
-                select_expressions: expressions,
-                from: None,
-                predicate: None,
-            });
-        }
+    /// Parses the body of a query whose `SELECT` is already consumed
+    pub(crate) fn parse_select(&mut self) -> Result<Select, DBError> {
+        let distinct = if self
+            .consume_if(TokenKind::Keyword(Keyword::Distinct))
+            .is_some()
+        {
+            true
+        } else {
+            // ALL is the default, it can be spelled out
+            self.consume_if(TokenKind::Keyword(Keyword::All));
+            false
+        };
+
+        let projection =
+            self.parse_seperated(Symbol::Comma, |parser| parser.parse_select_item())?;
 
-        let table_name = self.expect_identifier()?;
+        let from = if self.consume_if(TokenKind::Keyword(Keyword::From)).is_some() {
+            self.parse_seperated(Symbol::Comma, |parser| parser.parse_table_reference())?
+        } else {
+            Vec::new()
+        };
 
         let predicate = self.parse_predicate()?;
 
-        Ok(Statement::Select {
-            select_expressions: expressions,
-            from: Some(table_name),
+        let group_by = if self
+            .consume_if(TokenKind::Keyword(Keyword::Group))
+            .is_some()
+        {
+            self.expect(TokenKind::Keyword(Keyword::By))?;
+            self.parse_separated_expressions(Symbol::Comma)?
+        } else {
+            Vec::new()
+        };
+
+        let having = if self
+            .consume_if(TokenKind::Keyword(Keyword::Having))
+            .is_some()
+        {
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
+        let windows = if self
+            .consume_if(TokenKind::Keyword(Keyword::Window))
+            .is_some()
+        {
+            self.parse_named_windows()?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Select {
+            distinct,
+            projection,
+            from,
             predicate,
+            group_by,
+            having,
+            windows,
         })
     }
+
+    pub(crate) fn parse_select_item(&mut self) -> Result<SelectItem, DBError> {
+        if self.consume_if(TokenKind::Symbol(Symbol::Star)).is_some() {
+            return Ok(SelectItem::Wildcard);
+        }
+
+        // look past `a.b.` for a trailing `*`
+        let mut ahead = 0;
+        while self.peek_nth_is_identifier(ahead)
+            && self.peek_nth_is(ahead + 1, TokenKind::Symbol(Symbol::Dot))
+        {
+            if self.peek_nth_is(ahead + 2, TokenKind::Symbol(Symbol::Star)) {
+                let table = self.expect_compound_identifier()?;
+                self.expect(TokenKind::Symbol(Symbol::Dot))?;
+                self.expect(TokenKind::Symbol(Symbol::Star))?;
+                return Ok(SelectItem::QualifiedWildcard(table));
+            }
+            ahead += 2;
+        }
+
+        let expression = self.parse_expression()?;
+        let alias = self.parse_alias()?;
+
+        Ok(SelectItem::Expression { expression, alias })
+    }
+
+    /// Parses a table followed by any number of joins, which group left to right
+    pub(crate) fn parse_table_reference(&mut self) -> Result<TableReference, DBError> {
+        let mut left = self.parse_table_factor()?;
+
+        while let Some(join_type) = self.parse_join_type()? {
+            let right = self.parse_table_factor()?;
+
+            let constraint = if join_type == JoinType::Cross {
+                None
+            } else {
+                Some(
+                    match self.consume_keyword_of(&[Keyword::On, Keyword::Using]) {
+                        Some(Keyword::On) => JoinConstraint::On(self.parse_expression()?),
+                        Some(Keyword::Using) => {
+                            JoinConstraint::Using(self.parse_identifier_list()?)
+                        }
+                        _ => return self.unexpected(),
+                    },
+                )
+            };
+
+            left = TableReference::Join {
+                left: Box::new(left),
+                right: Box::new(right),
+                join_type,
+                constraint,
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn parse_table_factor(&mut self) -> Result<TableReference, DBError> {
+        if self
+            .consume_if(TokenKind::Symbol(Symbol::OpenParanthesis))
+            .is_some()
+        {
+            let query = self.parse_query()?;
+            self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+            let alias = self.parse_alias()?;
+            return Ok(TableReference::Derived {
+                query: Box::new(query),
+                alias,
+            });
+        }
+
+        let name = self.expect_compound_identifier()?;
+        let alias = self.parse_alias()?;
+        Ok(TableReference::Table { name, alias })
+    }
+
+    /// Consumes the keywords that introduce a join up to and including `JOIN`
+    fn parse_join_type(&mut self) -> Result<Option<JoinType>, DBError> {
+        // LEFT, RIGHT and FULL are non-reserved, they name columns and functions elsewhere
+        let join_type = match self.peek_keyword().or_else(|| self.peek_non_reserved()) {
+            Some(Keyword::Join) => JoinType::Inner,
+            Some(Keyword::Inner) => JoinType::Inner,
+            Some(Keyword::Left) => JoinType::Left,
+            Some(Keyword::Right) => JoinType::Right,
+            Some(Keyword::Full) => JoinType::Full,
+            Some(Keyword::Cross) => JoinType::Cross,
+            _ => return Ok(None),
+        };
+
+        if self.consume_if(TokenKind::Keyword(Keyword::Join)).is_none() {
+            // the join type keyword, then an optional OUTER for the outer joins
+            self.get_next_token()?;
+            if matches!(join_type, JoinType::Left | JoinType::Right | JoinType::Full) {
+                self.consume_if(TokenKind::Keyword(Keyword::Outer));
+            }
+            self.expect(TokenKind::Keyword(Keyword::Join))?;
+        }
+
+        Ok(Some(join_type))
+    }
+
+    /// Checks whether an outer join starts here, its join type is not an alias
+    pub(crate) fn at_outer_join(&mut self) -> bool {
+        matches!(
+            self.peek_non_reserved(),
+            Some(Keyword::Left | Keyword::Right | Keyword::Full)
+        ) && (self.peek_nth_is(1, TokenKind::Keyword(Keyword::Join))
+            || self.peek_nth_is(1, TokenKind::Keyword(Keyword::Outer)))
+    }
+
+    /// Parses an optional `ORDER BY expr [ASC | DESC] [NULLS FIRST | LAST], ...`
+    pub(crate) fn parse_order_by(&mut self) -> Result<Vec<OrderByExpression>, DBError> {
+        if self
+            .consume_if(TokenKind::Keyword(Keyword::Order))
+            .is_none()
+        {
+            return Ok(Vec::new());
+        }
+        self.expect(TokenKind::Keyword(Keyword::By))?;
+
+        self.parse_seperated(Symbol::Comma, |parser| {
+            let expression = parser.parse_expression()?;
+
+            let direction = if parser
+                .consume_if(TokenKind::Keyword(Keyword::Asc))
+                .is_some()
+            {
+                Some(SortDirection::Asc)
+            } else if parser
+                .consume_if(TokenKind::Keyword(Keyword::Desc))
+                .is_some()
+            {
+                Some(SortDirection::Desc)
+            } else {
+                None
+            };
+
+            // NULLS, FIRST and LAST are non-reserved, they only mean something here
+            let nulls = if parser.consume_non_reserved(Keyword::Nulls) {
+                if parser.consume_non_reserved(Keyword::First) {
+                    Some(NullsOrder::First)
+                } else {
+                    parser.expect_non_reserved(Keyword::Last)?;
+                    Some(NullsOrder::Last)
+                }
+            } else {
+                None
+            };
+
+            Ok(OrderByExpression {
+                expression,
+                direction,
+                nulls,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        JoinConstraint, JoinType, NullsOrder, OrderByExpression, Select, SelectItem, SortDirection,
+        TableReference,
+    };
+    use crate::{
+        lexer::literal::Literal,
+        parse,
+        parser::{
+            expression::Expression,
+            ident::{CompoundIdent, Ident},
+            statements::{
+                query::{Query, SetExpression},
+                Statement,
+            },
+        },
+    };
+
+    fn query(sql: &str) -> Query {
+        match parse(sql).unwrap().remove(0) {
+            Statement::Query(query) => *query,
+            statement => panic!("not a query: {:?}", statement),
+        }
+    }
+
+    fn select(sql: &str) -> Select {
+        match query(sql).body {
+            SetExpression::Select(select) => *select,
+            body => panic!("not a single SELECT: {:?}", body),
+        }
+    }
+
+    fn name(parts: &[&str]) -> CompoundIdent {
+        CompoundIdent(parts.iter().map(|&part| Ident::new(part)).collect())
+    }
+
+    #[test]
+    fn parses_the_projection() {
+        let select = select("SELECT DISTINCT *, t.*, a AS x, b y, c FROM t;");
+        assert!(select.distinct);
+        assert_eq!(
+            select.projection,
+            [
+                SelectItem::Wildcard,
+                SelectItem::QualifiedWildcard(name(&["t"])),
+                SelectItem::Expression {
+                    expression: Expression::Ident(name(&["a"])),
+                    alias: Some(Ident::new("x")),
+                },
+                SelectItem::Expression {
+                    expression: Expression::Ident(name(&["b"])),
+                    alias: Some(Ident::new("y")),
+                },
+                SelectItem::Expression {
+                    expression: Expression::Ident(name(&["c"])),
+                    alias: None,
+                },
+            ]
+        );
+        assert!(!self::select("SELECT ALL a;").distinct);
+    }
+
+    #[test]
+    fn parses_grouping_and_ordering() {
+        let query = query(
+            "SELECT a, count(*) FROM t WHERE b GROUP BY a HAVING count(*) > 1 \
+             ORDER BY a DESC NULLS FIRST, 2 LIMIT 10 OFFSET 5;",
+        );
+        let SetExpression::Select(select) = &query.body else {
+            panic!("not a single SELECT");
+        };
+        assert!(select.predicate.is_some());
+        assert_eq!(select.group_by, [Expression::Ident(name(&["a"]))]);
+        assert!(select.having.is_some());
+        assert_eq!(
+            query.order_by,
+            [
+                OrderByExpression {
+                    expression: Expression::Ident(name(&["a"])),
+                    direction: Some(SortDirection::Desc),
+                    nulls: Some(NullsOrder::First),
+                },
+                OrderByExpression {
+                    expression: Expression::Literal(Literal::Integer(2)),
+                    direction: None,
+                    nulls: None,
+                },
+            ]
+        );
+        assert_eq!(query.limit, Some(Expression::Literal(Literal::Integer(10))));
+        assert_eq!(query.offset, Some(Expression::Literal(Literal::Integer(5))));
+    }
+
+    #[test]
+    fn ordering_words_are_names_elsewhere() {
+        let select = select("SELECT nulls, first, last FROM t WHERE nulls = 1 AND offset > 0;");
+        assert_eq!(select.projection.len(), 3);
+        assert!(select.predicate.is_some());
+
+        let query = query("SELECT offset FROM t ORDER BY nulls NULLS LAST LIMIT 1 OFFSET offset;");
+        assert_eq!(
+            query.order_by[0].expression,
+            Expression::Ident(name(&["nulls"]))
+        );
+        assert_eq!(query.order_by[0].nulls, Some(NullsOrder::Last));
+        assert_eq!(query.offset, Some(Expression::Ident(name(&["offset"]))));
+
+        // an unquoted OFFSET after a table or column continues the query, quoted it's an alias
+        let query = self::query("SELECT a offset 1;");
+        assert_eq!(query.offset, Some(Expression::Literal(Literal::Integer(1))));
+        let query = self::query("SELECT a FROM t \"offset\" OFFSET 1;");
+        assert!(query.offset.is_some());
+        let SetExpression::Select(select) = query.body else {
+            panic!("not a single SELECT");
+        };
+        assert_eq!(select.from, vec![table(&["t"], Some("offset"))]);
+    }
+
+    #[test]
+    fn rejects_incomplete_clauses() {
+        for sql in [
+            "SELECT a FROM;",
+            "SELECT a FROM t GROUP a;",
+            "SELECT a FROM t ORDER BY;",
+            "SELECT a FROM t LIMIT;",
+            "SELECT a AS FROM t;",
+        ] {
+            assert!(parse(sql).is_err(), "{}", sql);
+        }
+    }
+
+    fn table(parts: &[&str], alias: Option<&str>) -> TableReference {
+        TableReference::Table {
+            name: name(parts),
+            alias: alias.map(Ident::new),
+        }
+    }
+
+    #[test]
+    fn joins_group_left_to_right() {
+        let from = select("SELECT * FROM a x JOIN b ON TRUE CROSS JOIN c, d AS y;").from;
+        assert_eq!(
+            from,
+            [
+                TableReference::Join {
+                    left: Box::new(TableReference::Join {
+                        left: Box::new(table(&["a"], Some("x"))),
+                        right: Box::new(table(&["b"], None)),
+                        join_type: JoinType::Inner,
+                        constraint: Some(JoinConstraint::On(Expression::Literal(
+                            Literal::Boolean(true)
+                        ))),
+                    }),
+                    right: Box::new(table(&["c"], None)),
+                    join_type: JoinType::Cross,
+                    constraint: None,
+                },
+                table(&["d"], Some("y")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_outer_joins() {
+        let from = select(
+            "SELECT * FROM a LEFT OUTER JOIN b USING (id) RIGHT JOIN c ON TRUE FULL OUTER JOIN d ON TRUE;",
+        )
+        .from;
+        let mut join_types = Vec::new();
+        let mut reference = &from[0];
+        while let TableReference::Join {
+            left, join_type, ..
+        } = reference
+        {
+            join_types.push(*join_type);
+            reference = left;
+        }
+        assert_eq!(
+            join_types,
+            [JoinType::Full, JoinType::Right, JoinType::Left]
+        );
+        assert!(parse("SELECT * FROM a JOIN b;").is_err());
+        assert!(parse("SELECT * FROM a LEFT b;").is_err());
+        assert!(parse("SELECT * FROM a CROSS JOIN b ON TRUE;").is_err());
+    }
+
+    #[test]
+    fn derived_tables_take_an_alias() {
+        let from = select("SELECT * FROM (SELECT 1) AS d, (SELECT 2) e;").from;
+        let aliases: Vec<_> = from
+            .iter()
+            .map(|reference| match reference {
+                TableReference::Derived { alias, .. } => alias.clone(),
+                reference => panic!("not a derived table: {:?}", reference),
+            })
+            .collect();
+        assert_eq!(aliases, [Some(Ident::new("d")), Some(Ident::new("e"))]);
+    }
 }
@@ -1,25 +1,37 @@
+use std::fmt::Display;
+
 use crate::{
-    common::position::Span,
     error::DBError,
     lexer::{keyword::Keyword, symbol::Symbol, token::TokenKind, Token},
-    parser::expression::Expression,
+    parser::ident::Ident,
+    parser::{expression::Expression, operators::assignment::AssignmentOperator},
     Parser,
 };
 
-use super::Statement;
+use super::{query::With, Statement};
 
 #[derive(Debug, PartialEq)]
-pub(crate) struct UpdateSet {
-    pub(crate) column: Span,
-    pub(crate) value: Expression,
+pub struct UpdateSet {
+    pub column: Ident,
+    pub operator: AssignmentOperator,
+    pub value: Expression,
+}
+
+impl Display for UpdateSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.column, self.operator, self.value)
+    }
 }
 
 impl<Tokens> Parser<Tokens>
 where
     Tokens: Iterator<Item = Result<Token, DBError>>,
 {
-    pub(crate) fn parse_update_statement(&mut self) -> Result<Statement, DBError> {
-        let table_name = self.expect_identifier()?;
+    pub(crate) fn parse_update_statement(
+        &mut self,
+        with: Option<With>,
+    ) -> Result<Statement, DBError> {
+        let table_name = self.expect_compound_identifier()?;
 
         self.expect(TokenKind::Keyword(Keyword::Set))?;
 
@@ -28,6 +40,7 @@ where
         let predicate = self.parse_predicate()?;
 
         Ok(Statement::Update {
+            with,
             table_name,
             set: update_set,
             predicate,
@@ -37,10 +50,78 @@ where
     pub(crate) fn parse_update_set(&mut self) -> Result<UpdateSet, DBError> {
         let column = self.expect_identifier()?;
 
-        self.expect(TokenKind::Symbol(Symbol::Equal))?;
+        let token = self.get_next_token()?;
+        let operator = match token.kind {
+            TokenKind::Symbol(symbol) => AssignmentOperator::match_symbol(&symbol),
+            _ => None,
+        };
+        let Some(operator) = operator else {
+            return Err(DBError::Unexpected {
+                found: token,
+                expected: [
+                    Symbol::Equal,
+                    Symbol::PlusEquals,
+                    Symbol::MinusEquals,
+                    Symbol::StarEquals,
+                    Symbol::DivideEquals,
+                    Symbol::PercentEquals,
+                ]
+                .into_iter()
+                .map(|symbol| TokenKind::Symbol(symbol).into())
+                .collect(),
+            });
+        };
 
         let value = self.parse_expression()?;
 
-        Ok(UpdateSet { column, value })
+        Ok(UpdateSet {
+            column,
+            operator,
+            value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::{DBError, Expected},
+        lexer::{symbol::Symbol, token::TokenKind},
+        parse,
+        parser::{operators::assignment::AssignmentOperator, statements::Statement},
+    };
+
+    #[test]
+    fn parses_compound_assignments() {
+        let Statement::Update { set, predicate, .. } =
+            parse("UPDATE t SET a = 1, b += 2, c -= 3, d *= 4, e /= 5, f %= 6 WHERE id = 1;")
+                .unwrap()
+                .remove(0)
+        else {
+            panic!("not an UPDATE");
+        };
+        let operators: Vec<_> = set.iter().map(|set| set.operator).collect();
+        assert_eq!(
+            operators,
+            [
+                AssignmentOperator::Assign,
+                AssignmentOperator::AddAssign,
+                AssignmentOperator::SubAssign,
+                AssignmentOperator::MulAssign,
+                AssignmentOperator::DivAssign,
+                AssignmentOperator::ModAssign,
+            ]
+        );
+        assert!(predicate.is_some());
+    }
+
+    #[test]
+    fn lists_the_assignment_operators() {
+        let errors = parse("UPDATE t SET a 1;").unwrap_err();
+        let [DBError::Unexpected { expected, .. }] = errors.errors() else {
+            panic!("{}", errors);
+        };
+        assert_eq!(expected.len(), 6);
+        assert!(expected.contains(&Expected::Token(TokenKind::Symbol(Symbol::PercentEquals))));
     }
 }
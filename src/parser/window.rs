@@ -0,0 +1,416 @@
+use std::fmt::Display;
+
+use crate::{
+    common::position::Span,
+    error::{DBError, FrameError},
+    lexer::{keyword::Keyword, symbol::Symbol, token::TokenKind, Token},
+    Parser,
+};
+
+use super::{
+    comma_separated, expression::Expression, ident::Ident, statements::select::OrderByExpression,
+};
+
+/// What follows `OVER`: the name of a window or an inline specification
+#[derive(Debug, PartialEq)]
+pub enum WindowType {
+    Named(Ident),
+    Specification(WindowSpecification),
+}
+
+/// `([base] [PARTITION BY ...] [ORDER BY ...] [frame])`
+#[derive(Debug, PartialEq)]
+pub struct WindowSpecification {
+    /// A window of the WINDOW clause that this one extends
+    pub base: Option<Ident>,
+    pub partition_by: Vec<Expression>,
+    pub order_by: Vec<OrderByExpression>,
+    pub frame: Option<WindowFrame>,
+}
+
+/// `name AS (specification)` in the WINDOW clause
+#[derive(Debug, PartialEq)]
+pub struct NamedWindow {
+    pub name: Ident,
+    pub specification: WindowSpecification,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WindowFrame {
+    pub units: FrameUnits,
+    pub start: FrameBound,
+    /// Defaults to the current row when only the start is given
+    pub end: Option<FrameBound>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameUnits {
+    Rows,
+    Range,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(Box<Expression>),
+    CurrentRow,
+    Following(Box<Expression>),
+    UnboundedFollowing,
+}
+
+impl FrameBound {
+    /// Orders the bounds from the first row of the partition to the last, offsets aside
+    fn rank(&self) -> u8 {
+        match self {
+            FrameBound::UnboundedPreceding => 0,
+            FrameBound::Preceding(_) => 1,
+            FrameBound::CurrentRow => 2,
+            FrameBound::Following(_) => 3,
+            FrameBound::UnboundedFollowing => 4,
+        }
+    }
+}
+
+impl Display for WindowType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowType::Named(name) => write!(f, "{}", name),
+            WindowType::Specification(specification) => write!(f, "({})", specification),
+        }
+    }
+}
+
+impl Display for WindowSpecification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(base) = &self.base {
+            parts.push(base.to_string());
+        }
+        if !self.partition_by.is_empty() {
+            parts.push(format!(
+                "PARTITION BY {}",
+                comma_separated(&self.partition_by)
+            ));
+        }
+        if !self.order_by.is_empty() {
+            parts.push(format!("ORDER BY {}", comma_separated(&self.order_by)));
+        }
+        if let Some(frame) = &self.frame {
+            parts.push(frame.to_string());
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+impl Display for NamedWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} AS ({})", self.name, self.specification)
+    }
+}
+
+impl Display for WindowFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.end {
+            Some(end) => write!(f, "{} BETWEEN {} AND {}", self.units, self.start, end),
+            None => write!(f, "{} {}", self.units, self.start),
+        }
+    }
+}
+
+impl Display for FrameUnits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameUnits::Rows => write!(f, "ROWS"),
+            FrameUnits::Range => write!(f, "RANGE"),
+        }
+    }
+}
+
+impl Display for FrameBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameBound::UnboundedPreceding => write!(f, "UNBOUNDED PRECEDING"),
+            FrameBound::Preceding(offset) => write!(f, "{} PRECEDING", offset),
+            FrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            FrameBound::Following(offset) => write!(f, "{} FOLLOWING", offset),
+            FrameBound::UnboundedFollowing => write!(f, "UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+impl<Tokens> Parser<Tokens>
+where
+    Tokens: Iterator<Item = Result<Token, DBError>>,
+{
+    /// Parses the window of a call after `OVER`
+    pub(crate) fn parse_window_type(&mut self) -> Result<WindowType, DBError> {
+        if self
+            .consume_if(TokenKind::Symbol(Symbol::OpenParanthesis))
+            .is_none()
+        {
+            return Ok(WindowType::Named(self.expect_identifier()?));
+        }
+
+        let specification = self.parse_window_specification()?;
+        self.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+        Ok(WindowType::Specification(specification))
+    }
+
+    /// Parses the named windows of a `WINDOW` clause, `WINDOW` is already consumed
+    pub(crate) fn parse_named_windows(&mut self) -> Result<Vec<NamedWindow>, DBError> {
+        self.parse_seperated(Symbol::Comma, |parser| {
+            let name = parser.expect_identifier()?;
+            parser.expect(TokenKind::Keyword(Keyword::As))?;
+            parser.expect(TokenKind::Symbol(Symbol::OpenParanthesis))?;
+            let specification = parser.parse_window_specification()?;
+            parser.expect(TokenKind::Symbol(Symbol::CloseParanthesis))?;
+            Ok(NamedWindow {
+                name,
+                specification,
+            })
+        })
+    }
+
+    /// Parses the inside of a window's parentheses
+    fn parse_window_specification(&mut self) -> Result<WindowSpecification, DBError> {
+        // ROWS and RANGE are non-reserved, unquoted they start the frame rather than name a window
+        let units = |keyword| match keyword {
+            Some(Keyword::Rows) => Some(FrameUnits::Rows),
+            Some(Keyword::Range) => Some(FrameUnits::Range),
+            _ => None,
+        };
+
+        // PARTITION is non-reserved as well, it only starts the partitioning before BY
+        let at_partition_by = |parser: &mut Self| {
+            parser.peek_non_reserved() == Some(Keyword::Partition)
+                && parser.peek_nth_is(1, TokenKind::Keyword(Keyword::By))
+        };
+
+        let base = if self.peek_nth_is_identifier(0)
+            && units(self.peek_non_reserved()).is_none()
+            && !at_partition_by(self)
+        {
+            Some(self.expect_identifier()?)
+        } else {
+            None
+        };
+
+        let partition_by = if at_partition_by(self) {
+            self.get_next_token()?;
+            self.get_next_token()?;
+            self.parse_separated_expressions(Symbol::Comma)?
+        } else {
+            self.note_expected(TokenKind::Keyword(Keyword::Partition));
+            Vec::new()
+        };
+
+        let order_by = self.parse_order_by()?;
+
+        let frame = match units(self.peek_non_reserved()) {
+            Some(units) => {
+                self.get_next_token()?;
+                Some(self.parse_window_frame(units)?)
+            }
+            None => None,
+        };
+
+        Ok(WindowSpecification {
+            base,
+            partition_by,
+            order_by,
+            frame,
+        })
+    }
+
+    /// Parses `BETWEEN start AND end` or a lone start bound, rejecting frames that
+    /// can't hold any row
+    fn parse_window_frame(&mut self, units: FrameUnits) -> Result<WindowFrame, DBError> {
+        let between = self
+            .consume_if(TokenKind::Keyword(Keyword::Between))
+            .is_some();
+
+        let (start, start_span) = self.parse_spanned_frame_bound()?;
+        if start == FrameBound::UnboundedFollowing {
+            return Err(DBError::InvalidFrame {
+                reason: FrameError::StartsUnboundedFollowing,
+                span: start_span,
+            });
+        }
+
+        if !between {
+            // the frame ends at the current row
+            if start.rank() > FrameBound::CurrentRow.rank() {
+                return Err(DBError::InvalidFrame {
+                    reason: FrameError::StartsAfterEnd,
+                    span: start_span,
+                });
+            }
+            return Ok(WindowFrame {
+                units,
+                start,
+                end: None,
+            });
+        }
+
+        self.expect(TokenKind::Keyword(Keyword::And))?;
+        let (end, end_span) = self.parse_spanned_frame_bound()?;
+        if end == FrameBound::UnboundedPreceding {
+            return Err(DBError::InvalidFrame {
+                reason: FrameError::EndsUnboundedPreceding,
+                span: end_span,
+            });
+        }
+        if start.rank() > end.rank() {
+            return Err(DBError::InvalidFrame {
+                reason: FrameError::StartsAfterEnd,
+                span: Span {
+                    start: start_span.start,
+                    end: end_span.end,
+                },
+            });
+        }
+
+        Ok(WindowFrame {
+            units,
+            start,
+            end: Some(end),
+        })
+    }
+
+    fn parse_spanned_frame_bound(&mut self) -> Result<(FrameBound, Span), DBError> {
+        let start = match self.tokens.peek() {
+            Some(Ok(token)) => token.span.start,
+            _ => self.end_span().start,
+        };
+        let bound = self.parse_frame_bound()?;
+        let end = self.previous_span().end;
+        Ok((bound, Span { start, end }))
+    }
+
+    fn parse_frame_bound(&mut self) -> Result<FrameBound, DBError> {
+        // CURRENT and ROW are non-reserved, `current` alone is a column in an offset
+        if self.peek_non_reserved() == Some(Keyword::Current)
+            && self.peek_nth_non_reserved(1) == Some(Keyword::Row)
+        {
+            self.get_next_token()?;
+            self.get_next_token()?;
+            return Ok(FrameBound::CurrentRow);
+        }
+        self.note_expected(TokenKind::Keyword(Keyword::Current));
+
+        // UNBOUNDED, PRECEDING and FOLLOWING are non-reserved, `unbounded` alone is a column
+        if self.peek_non_reserved() == Some(Keyword::Unbounded) {
+            match self.peek_nth_non_reserved(1) {
+                Some(Keyword::Preceding) => {
+                    self.get_next_token()?;
+                    self.get_next_token()?;
+                    return Ok(FrameBound::UnboundedPreceding);
+                }
+                Some(Keyword::Following) => {
+                    self.get_next_token()?;
+                    self.get_next_token()?;
+                    return Ok(FrameBound::UnboundedFollowing);
+                }
+                _ => {}
+            }
+        }
+        self.note_expected(TokenKind::Keyword(Keyword::Unbounded));
+
+        let offset = Box::new(self.parse_expression()?);
+        if self.consume_non_reserved(Keyword::Preceding) {
+            return Ok(FrameBound::Preceding(offset));
+        }
+        self.expect_non_reserved(Keyword::Following)?;
+        Ok(FrameBound::Following(offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::{DBError, FrameError},
+        parse,
+    };
+
+    fn frame_error(sql: &str) -> FrameError {
+        let errors = parse(sql).unwrap_err();
+        match errors.errors() {
+            [DBError::InvalidFrame { reason, .. }] => *reason,
+            errors => panic!("{}: {:?}", sql, errors),
+        }
+    }
+
+    #[test]
+    fn frame_words_are_names_outside_frames() {
+        let sql = "SELECT sum(rows) OVER (w ROWS BETWEEN current PRECEDING AND CURRENT ROW), range FROM t WINDOW w AS (ORDER BY row);";
+        assert_eq!(
+            parse(sql).unwrap()[0].to_string(),
+            "SELECT sum(\"rows\") OVER (w ROWS BETWEEN \"current\" PRECEDING AND CURRENT ROW), \"range\" FROM t WINDOW w AS (ORDER BY \"row\");"
+        );
+    }
+
+    #[test]
+    fn window_words_are_names_outside_windows() {
+        let sql = "SELECT preceding, following, unbounded, partition, count(*) over FROM t;";
+        assert_eq!(
+            parse(sql).unwrap()[0].to_string(),
+            "SELECT \"preceding\", \"following\", \"unbounded\", \"partition\", count(*) AS \"over\" FROM t;"
+        );
+
+        let sql = "SELECT sum(a) OVER (partition PARTITION BY partition ROWS BETWEEN unbounded + 1 PRECEDING AND UNBOUNDED FOLLOWING) FROM t WINDOW partition AS ();";
+        assert_eq!(
+            parse(sql).unwrap()[0].to_string(),
+            "SELECT sum(a) OVER (\"partition\" PARTITION BY \"partition\" ROWS BETWEEN \"unbounded\" + 1 PRECEDING AND UNBOUNDED FOLLOWING) FROM t WINDOW \"partition\" AS ();"
+        );
+    }
+
+    #[test]
+    fn quoted_frame_units_name_the_base_window() {
+        let sql =
+            "SELECT sum(a) OVER (\"rows\" RANGE UNBOUNDED PRECEDING) FROM t WINDOW \"rows\" AS ();";
+        let statements = parse(sql).unwrap();
+        assert_eq!(parse(&statements[0].to_string()).unwrap(), statements);
+    }
+
+    #[test]
+    fn rejects_frames_without_rows() {
+        assert_eq!(
+            frame_error("SELECT sum(a) OVER (ROWS UNBOUNDED FOLLOWING) FROM t;"),
+            FrameError::StartsUnboundedFollowing
+        );
+        assert_eq!(
+            frame_error(
+                "SELECT sum(a) OVER (ROWS BETWEEN 1 PRECEDING AND UNBOUNDED PRECEDING) FROM t;"
+            ),
+            FrameError::EndsUnboundedPreceding
+        );
+        assert_eq!(
+            frame_error("SELECT sum(a) OVER (ROWS BETWEEN 2 FOLLOWING AND 1 PRECEDING) FROM t;"),
+            FrameError::StartsAfterEnd
+        );
+        assert_eq!(
+            frame_error("SELECT sum(a) OVER (RANGE BETWEEN CURRENT ROW AND 1 PRECEDING) FROM t;"),
+            FrameError::StartsAfterEnd
+        );
+        assert_eq!(
+            frame_error("SELECT sum(a) OVER (ROWS 2 FOLLOWING) FROM t;"),
+            FrameError::StartsAfterEnd
+        );
+    }
+
+    #[test]
+    fn accepts_ordered_bounds() {
+        for frame in [
+            "ROWS UNBOUNDED PRECEDING",
+            "ROWS CURRENT ROW",
+            "ROWS BETWEEN 2 PRECEDING AND 1 PRECEDING",
+            "RANGE BETWEEN CURRENT ROW AND UNBOUNDED FOLLOWING",
+            "ROWS BETWEEN 1 FOLLOWING AND 3 FOLLOWING",
+        ] {
+            let sql = format!("SELECT sum(a) OVER ({}) FROM t;", frame);
+            if let Err(errors) = parse(&sql) {
+                panic!("{}\n{}", sql, errors);
+            }
+        }
+    }
+}
@@ -3,12 +3,24 @@ use std::io::{BufRead, BufReader, Read};
 use crate::common::position::{Position, Span};
 
 // iterates over a source by char. asumes source is valid utf8
+#[derive(Debug, Clone)]
 pub(crate) struct RawStatement {
     pub(crate) content: String,
     pub(crate) span: Span,
 }
 
 impl RawStatement {
+    /// A whole source as one statement, starting at the beginning
+    pub(crate) fn new(content: &str) -> Self {
+        let start = Position::default();
+        let mut end = start;
+        end += content;
+        Self {
+            content: content.to_string(),
+            span: Span { start, end },
+        }
+    }
+
     pub(crate) fn iter<'r>(&'r self) -> RawStatementIterator<'r> {
         RawStatementIterator::new(self)
     }
@@ -45,10 +57,12 @@ impl<'r> Iterator for RawStatementIterator<'r> {
     type Item = Atom;
 
     fn next(&mut self) -> Option<Self::Item> {
+        // positions are absolute in the source, content starts at the statement
+        let offset = self.position.index - self.raw_statement.span.start.index;
         let ch = self
             .raw_statement
             .content
-            .get(self.position.index..)
+            .get(offset..)
             .and_then(|s| s.chars().next());
         ch.map(|c| {
             // update cursor and position
@@ -81,14 +95,170 @@ where
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ScanState {
+    #[default]
+    Normal,
+    // saw a `-` that may start a `--` comment
+    Dash,
+    // saw a `/` that may start a `/*` comment
+    Slash,
+    LineComment,
+    BlockComment {
+        depth: usize,
+    },
+    // saw a `*` inside a block comment that may close it
+    BlockCommentStar {
+        depth: usize,
+    },
+    // saw a `/` inside a block comment that may open a nested one
+    BlockCommentSlash {
+        depth: usize,
+    },
+    Quoted {
+        quote: u8,
+        backslash_escapes: bool,
+    },
+    // saw a backslash inside an E'...' string
+    Escaped {
+        quote: u8,
+    },
+    // saw a quote that either closes the literal or is the first half of a doubled quote
+    QuoteEnd {
+        quote: u8,
+        backslash_escapes: bool,
+    },
+}
+
+/// Finds the semicolons that actually end a statement, skipping over the ones
+/// inside string literals, quoted identifiers and comments.
+/// Works byte by byte, so it can be fed the source in chunks of any size
+#[derive(Debug, Default)]
+pub struct StatementScanner {
+    state: ScanState,
+    // whether anything other than whitespace and comments follows the last semicolon
+    pending: bool,
+    previous: [u8; 2],
+}
+
+impl StatementScanner {
+    /// Advances the scanner by a byte, returns `true` if it is a statement ending semicolon
+    pub fn step(&mut self, byte: u8) -> bool {
+        let terminated = self.transition(byte);
+        self.previous = [self.previous[1], byte];
+        terminated
+    }
+
+    /// Returns `true` when everything fed so far forms complete statements
+    pub fn is_complete(&self) -> bool {
+        matches!(self.state, ScanState::Normal | ScanState::LineComment) && !self.pending
+    }
+
+    fn transition(&mut self, byte: u8) -> bool {
+        use ScanState::*;
+
+        // resolve the states that needed to look at this byte first
+        match self.state {
+            Dash if byte == b'-' => {
+                self.state = LineComment;
+                return false;
+            }
+            Slash if byte == b'*' => {
+                self.state = BlockComment { depth: 1 };
+                return false;
+            }
+            Dash | Slash => {
+                // it was an operator after all
+                self.pending = true;
+                self.state = Normal;
+            }
+            QuoteEnd {
+                quote,
+                backslash_escapes,
+            } => {
+                self.state = Normal;
+                if byte == quote {
+                    self.state = Quoted {
+                        quote,
+                        backslash_escapes,
+                    };
+                    return false;
+                }
+            }
+            _ => {}
+        }
+
+        self.state = match self.state {
+            Normal => match byte {
+                b';' => {
+                    self.pending = false;
+                    return true;
+                }
+                b'-' => Dash,
+                b'/' => Slash,
+                b'#' => LineComment,
+                b'\'' | b'"' | b'`' => {
+                    self.pending = true;
+                    Quoted {
+                        quote: byte,
+                        backslash_escapes: byte == b'\'' && self.is_escape_string_prefix(),
+                    }
+                }
+                byte => {
+                    self.pending |= !byte.is_ascii_whitespace();
+                    Normal
+                }
+            },
+            LineComment if byte == b'\n' => Normal,
+            LineComment => LineComment,
+            BlockComment { depth } | BlockCommentStar { depth } | BlockCommentSlash { depth } => {
+                match (self.state, byte) {
+                    (BlockCommentStar { .. }, b'/') if depth == 1 => Normal,
+                    (BlockCommentStar { .. }, b'/') => BlockComment { depth: depth - 1 },
+                    (BlockCommentSlash { .. }, b'*') => BlockComment { depth: depth + 1 },
+                    (_, b'*') => BlockCommentStar { depth },
+                    (_, b'/') => BlockCommentSlash { depth },
+                    _ => BlockComment { depth },
+                }
+            }
+            Quoted {
+                quote,
+                backslash_escapes,
+            } => match byte {
+                byte if byte == quote => QuoteEnd {
+                    quote,
+                    backslash_escapes,
+                },
+                b'\\' if backslash_escapes => Escaped { quote },
+                _ => self.state,
+            },
+            Escaped { quote } => Quoted {
+                quote,
+                backslash_escapes: true,
+            },
+            Dash | Slash | QuoteEnd { .. } => unreachable!("resolved above"),
+        };
+
+        false
+    }
+
+    // an `E` or `e` that starts a word right before the opening quote
+    fn is_escape_string_prefix(&self) -> bool {
+        let [before, previous] = self.previous;
+        matches!(previous, b'e' | b'E') && !(before.is_ascii_alphanumeric() || before == b'_')
+    }
+}
+
 /// Responsibility of this struct is to read from the source
-/// and yield statements split by semicolon
+/// and yield statements split by semicolon.
+/// Semicolons inside strings, quoted identifiers and comments don't split.
 /// Assumes the source is valid utf8
 pub(crate) struct SourceIterator<R>
 where
     R: Read,
 {
     source: BufReader<R>,
+    scanner: StatementScanner,
     pub(crate) position: Position,
 }
 
@@ -96,11 +266,10 @@ impl<R> SourceIterator<R>
 where
     R: Read,
 {
-    const SEPERATOR: u8 = b';';
-
     pub(crate) fn new(source: R) -> Self {
         Self {
             source: BufReader::new(source),
+            scanner: StatementScanner::default(),
             position: Position::default(),
         }
     }
@@ -115,14 +284,29 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         let mut statement = Vec::new();
         let start_position = self.position;
-        // take until semicolon
-        let read = self
-            .source
-            .read_until(Self::SEPERATOR, &mut statement)
-            .inspect_err(|e| eprintln!("Error reading source: {}", e))
-            .ok()?;
-
-        if read == 0 {
+        // take until a semicolon that ends the statement
+        loop {
+            let buffer = self
+                .source
+                .fill_buf()
+                .inspect_err(|e| eprintln!("Error reading source: {}", e))
+                .ok()?;
+
+            if buffer.is_empty() {
+                break;
+            }
+
+            let end = buffer.iter().position(|&byte| self.scanner.step(byte));
+            let read = end.map_or(buffer.len(), |index| index + 1);
+            statement.extend_from_slice(&buffer[..read]);
+            self.source.consume(read);
+
+            if end.is_some() {
+                break;
+            }
+        }
+
+        if statement.is_empty() {
             return None;
         }
 
@@ -141,3 +325,73 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{SplitRawStatements, StatementScanner};
+
+    fn split(sql: &str) -> Vec<String> {
+        Cursor::new(sql)
+            .split_raw_statements()
+            .map(|statement| statement.content)
+            .collect()
+    }
+
+    #[test]
+    fn splits_at_semicolons() {
+        assert_eq!(
+            split("SELECT 1; SELECT 2;\n"),
+            ["SELECT 1;", " SELECT 2;", "\n"]
+        );
+        assert_eq!(split("SELECT 1"), ["SELECT 1"]);
+        assert!(split("").is_empty());
+    }
+
+    #[test]
+    fn semicolons_inside_quotes_and_comments_do_not_split() {
+        for sql in [
+            "SELECT 'a;b', 'it''s;';",
+            "SELECT \"a;\"\"b\";",
+            "SELECT E'\\';' FROM t;",
+            "SELECT 1 -- no; split\n;",
+            "SELECT /* a; /* nested; */ b; */ 1;",
+        ] {
+            assert_eq!(split(sql), [sql]);
+        }
+    }
+
+    #[test]
+    fn statements_start_where_the_previous_one_ended() {
+        let statements: Vec<_> = Cursor::new("SELECT 'é';\nSELECT 2;")
+            .split_raw_statements()
+            .collect();
+        let start = statements[1].span.start;
+        assert_eq!(start.index, 12);
+        assert_eq!(start.absolute.col, 11);
+        assert_eq!(statements[1].content, "\nSELECT 2;");
+    }
+
+    #[test]
+    fn scanner_tracks_incomplete_input() {
+        let mut scanner = StatementScanner::default();
+        assert!(scanner.is_complete());
+        let ends: Vec<bool> = b"SELECT ';'"
+            .iter()
+            .map(|&byte| scanner.step(byte))
+            .collect();
+        assert!(!ends.contains(&true));
+        assert!(!scanner.is_complete());
+        assert!(scanner.step(b';'));
+        assert!(scanner.is_complete());
+        b" -- trailing comment".iter().for_each(|&byte| {
+            scanner.step(byte);
+        });
+        assert!(scanner.is_complete());
+        b"\n/* open".iter().for_each(|&byte| {
+            scanner.step(byte);
+        });
+        assert!(!scanner.is_complete());
+    }
+}